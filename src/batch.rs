@@ -0,0 +1,169 @@
+use std::path::{Component, Path, PathBuf};
+use rayon::prelude::*;
+use serde_json::Value;
+use crate::document::{parse_document, Document, DocumentKind};
+use crate::error::{from_json_str, Error, Result, Warning};
+use crate::{read_file, write_json};
+
+const CONFIG_EXTENSIONS: &[&str] = &["json", "yaml", "yml", "toml"];
+
+/// File name without `.json` and a `.postman` / `.openapi` marker, `users.postman.json` gives `users`
+fn base_name(input: &Path) -> String {
+    let name = input.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let name = name.strip_suffix(".json").unwrap_or(&name);
+    let name = name.strip_suffix(".postman").or_else(|| name.strip_suffix(".openapi")).unwrap_or(name);
+    name.to_owned()
+}
+
+fn is_config(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().contains(".config."))
+}
+
+/// Per file config next to the input, `users.config.yaml` for `users.json`
+pub fn sibling_config(input: &Path) -> Option<PathBuf> {
+    let base_name = base_name(input);
+    CONFIG_EXTENSIONS.iter()
+        .map(|extension| input.with_file_name(format!("{}.config.{}", base_name, extension)))
+        .find(|config| config.is_file())
+}
+
+/// Where the conversion of `input` goes, mirroring its place under `base` into `output_dir`
+pub fn output_path(input: &Path, base: &Path, output_dir: &Path, to_postman: bool) -> PathBuf {
+    let relative = input.strip_prefix(base).unwrap_or(input);
+    let suffix = if to_postman { "postman" } else { "openapi" };
+    output_dir.join(relative).with_file_name(format!("{}.{}.json", base_name(input), suffix))
+}
+
+fn walk(dir: &Path, skip: &Path, inputs: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = std::fs::read_dir(dir).map_err(|err| Error::io(&dir.display().to_string(), err))?;
+    for entry in entries {
+        let path = entry.map_err(|err| Error::io(&dir.display().to_string(), err))?.path();
+        if path.is_dir() {
+            if std::fs::canonicalize(&path).ok().as_deref() != Some(skip) {
+                walk(&path, skip, inputs)?;
+            }
+        } else if path.extension().is_some_and(|extension| extension == "json") && !is_config(&path) {
+            inputs.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The json files under a directory, or the files matching a glob, with the directory their paths are
+/// mirrored from. Whatever lies in `output_dir` is left out so a second run does not convert its own output.
+pub fn find_inputs(pattern: &str, output_dir: &Path) -> Result<(PathBuf, Vec<PathBuf>)> {
+    let skip = std::fs::canonicalize(output_dir).unwrap_or_else(|_| output_dir.to_path_buf());
+    let mut inputs: Vec<PathBuf> = Vec::new();
+    let base = if Path::new(pattern).is_dir() {
+        walk(Path::new(pattern), &skip, &mut inputs)?;
+        PathBuf::from(pattern)
+    } else {
+        let paths = glob::glob(pattern).map_err(|err| Error::Invalid {
+            pointer: String::new(),
+            message: format!("bad glob {}: {}", pattern, err),
+        })?;
+        for path in paths {
+            let path = path.map_err(|err| Error::io(&err.path().display().to_string(), err.into()))?;
+            let in_output = std::fs::canonicalize(&path).is_ok_and(|path| path.starts_with(&skip));
+            if path.is_file() && !is_config(&path) && !in_output {
+                inputs.push(path);
+            }
+        }
+        // the directories before the first component holding a wildcard, the file's own directory without one
+        let is_wildcard = |component: &Component| matches!(component, Component::Normal(name) if name.to_string_lossy().contains(&['*', '?', '['][..]));
+        if Path::new(pattern).components().any(|component| is_wildcard(&component)) {
+            Path::new(pattern).components().take_while(|component| !is_wildcard(component)).collect()
+        } else {
+            Path::new(pattern).parent().map(Path::to_path_buf).unwrap_or_default()
+        }
+    };
+    inputs.sort();
+    Ok((base, inputs))
+}
+
+#[derive(Debug)]
+pub enum BatchStatus {
+    Converted,
+    /// Neither an OpenAPI document nor a postman collection
+    Skipped,
+    Failed(Error),
+}
+
+#[derive(Debug)]
+pub struct BatchOutcome {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub to_postman: bool,
+    pub warnings: Vec<Warning>,
+    pub status: BatchStatus,
+}
+
+/// Converts every input in parallel, `convert` turns one parsed input into the JSON to write
+pub fn run_batch<F>(inputs: &[PathBuf], base: &Path, output_dir: &Path, lenient: bool, convert: F) -> Vec<BatchOutcome>
+where
+    F: Fn(&Path, Document) -> Result<(Value, Vec<Warning>)> + Sync,
+{
+    inputs.par_iter().map(|input| {
+        let mut outcome = BatchOutcome {
+            input: input.clone(),
+            output: None,
+            to_postman: false,
+            warnings: Vec::new(),
+            status: BatchStatus::Converted,
+        };
+        let source = input.display().to_string();
+        let converted = read_file(input).and_then(|json| {
+            // JSON that does not parse is a broken input, only well formed JSON of another kind is skipped
+            let value: Value = from_json_str(&json, &source)?;
+            if DocumentKind::detect(&value).is_none() {
+                outcome.status = BatchStatus::Skipped;
+                return Ok(());
+            }
+            let (document, warnings) = parse_document(&json, &source, lenient)?;
+            outcome.warnings = warnings;
+            outcome.to_postman = matches!(document, Document::OpenApi3(_));
+            let (converted, warnings) = convert(input, document)?;
+            outcome.warnings.extend(warnings);
+            let output = output_path(input, base, output_dir, outcome.to_postman);
+            if let Some(parent) = output.parent() {
+                std::fs::create_dir_all(parent).map_err(|err| Error::io(&parent.display().to_string(), err))?;
+            }
+            write_json(&output, &converted, !outcome.to_postman)?;
+            outcome.output = Some(output);
+            Ok(())
+        });
+        if let Err(err) = converted {
+            outcome.status = BatchStatus::Failed(err);
+        }
+        outcome
+    }).collect()
+}
+
+/// One row per input, then the totals
+pub fn summary_table(outcomes: &[BatchOutcome]) -> String {
+    let rows: Vec<[String; 4]> = outcomes.iter().map(|outcome| {
+        let (direction, status) = match &outcome.status {
+            BatchStatus::Converted if outcome.to_postman => ("to-postman", String::from("ok")),
+            BatchStatus::Converted => ("to-openapi", String::from("ok")),
+            BatchStatus::Skipped => ("-", String::from("skipped")),
+            BatchStatus::Failed(err) => ("-", format!("failed: {}", err)),
+        };
+        [outcome.input.display().to_string(), direction.to_owned(), outcome.warnings.len().to_string(), status]
+    }).collect();
+    let header = [String::from("FILE"), String::from("DIRECTION"), String::from("WARNINGS"), String::from("STATUS")];
+    let width = |column: usize| rows.iter().chain(std::iter::once(&header)).map(|row| row[column].len()).max().unwrap_or(0);
+    let widths = [width(0), width(1), width(2)];
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        table.push_str(&format!("{:w0$}  {:w1$}  {:w2$}  {}\n", row[0], row[1], row[2], row[3], w0 = widths[0], w1 = widths[1], w2 = widths[2]));
+    }
+    let count = |status: fn(&BatchStatus) -> bool| outcomes.iter().filter(|outcome| status(&outcome.status)).count();
+    table.push_str(&format!(
+        "{} converted, {} with warnings, {} skipped, {} failed\n",
+        count(|status| matches!(status, BatchStatus::Converted)),
+        outcomes.iter().filter(|outcome| matches!(outcome.status, BatchStatus::Converted) && !outcome.warnings.is_empty()).count(),
+        count(|status| matches!(status, BatchStatus::Skipped)),
+        count(|status| matches!(status, BatchStatus::Failed(_))),
+    ));
+    table
+}
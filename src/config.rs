@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use crate::error::{from_json_str, from_json_value, Error, Result};
+use crate::{read_file, source_name};
+
+/// Key naming the config a config is layered on, relative to the file holding it
+pub const EXTENDS_KEY: &str = "extends";
+
+/// Parses JSON, YAML (`.yaml`, `.yml`) or TOML (`.toml`) by the file extension
+fn parse_config(text: &str, path: &Path) -> Result<Value> {
+    let source = source_name(path);
+    let parse_error = |message: String| Error::Parse {
+        source: source.clone(),
+        pointer: String::new(),
+        message,
+    };
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("yaml") | Some("yml") => serde_yaml::from_str(text).map_err(|err| parse_error(err.to_string())),
+        Some("toml") => toml::from_str(text).map_err(|err| parse_error(err.to_string())),
+        _ => from_json_str(text, &source),
+    }
+}
+
+/// Merges objects key by key, anything else (lists included) is replaced by the overlay
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    },
+                }
+            }
+        },
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn read_layers(path: &Path, seen: &mut Vec<PathBuf>) -> Result<Value> {
+    let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if seen.contains(&canonical) {
+        return Err(Error::Invalid {
+            pointer: format!("/{}", EXTENDS_KEY),
+            message: format!("config {} extends itself", source_name(path)),
+        });
+    }
+    seen.push(canonical);
+    let mut config = parse_config(&read_file(path)?, path)?;
+    match config.as_object_mut().and_then(|obj| obj.remove(EXTENDS_KEY)) {
+        None => Ok(config),
+        Some(Value::String(base)) => {
+            let mut layered = read_layers(&path.parent().unwrap_or_else(|| Path::new("")).join(base), seen)?;
+            merge(&mut layered, config);
+            Ok(layered)
+        },
+        Some(_) => Err(Error::Invalid {
+            pointer: format!("/{}", EXTENDS_KEY),
+            message: format!("`{}` of {} must be the path of a config file", EXTENDS_KEY, source_name(path)),
+        }),
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    key.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Points serde's unknown field message at the expected key spelled alike, `precedingpath` at `precedingPath`
+fn suggest_field(message: String) -> String {
+    if !message.starts_with("unknown field") {
+        return message;
+    }
+    let quoted: Vec<&str> = message.split('`').skip(1).step_by(2).collect();
+    let suggestion = quoted.split_first().and_then(|(unknown, expected)| {
+        expected.iter().find(|key| normalize_key(key) == normalize_key(unknown))
+    });
+    match suggestion {
+        Some(key) => format!("{}, did you mean `{}`?", message, key),
+        None => message,
+    }
+}
+
+/// Reads a JSON, YAML or TOML config into one of the option structs, layering it on the config it `extends`
+pub fn read_config<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let config = read_layers(path, &mut Vec::new())?;
+    from_json_value(config, &source_name(path)).map_err(|err| match err {
+        Error::Parse { source, pointer, message } => Error::Parse {
+            source,
+            pointer,
+            message: suggest_field(message),
+        },
+        err => err,
+    })
+}
+
+/// JSON Schema of a config file holding `T`, `extends` included
+pub fn config_schema<T: JsonSchema>() -> Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(T)).unwrap_or(Value::Null);
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        properties.insert(EXTENDS_KEY.to_owned(), serde_json::json!({
+            "description": "Config this one is layered on, relative to this file",
+            "type": "string"
+        }));
+    }
+    schema
+}
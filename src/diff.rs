@@ -0,0 +1,539 @@
+use std::collections::{BTreeMap, BTreeSet};
+use serde_json::Value;
+use crate::model::{OpenApi3, OpenApi3Components, OpenApi3OperationMethod, OpenApi3OperationMethodSecurity, OpenApi3Parameter, OpenApi3PathItem, OpenApi3Schema};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One difference between two documents, of an operation or of something within it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub method: String,
+    pub path: String,
+    /// What changed within the operation, empty when the operation itself was added, removed or edited
+    pub detail: String,
+    /// Whether a client written against the old document may fail against the new one
+    pub breaking: bool,
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let sign = match self.kind {
+            ChangeKind::Added => "+",
+            ChangeKind::Removed => "-",
+            ChangeKind::Changed => "~",
+        };
+        write!(f, "{} {} {}", sign, self.method.to_uppercase(), self.path)?;
+        if !self.detail.is_empty() {
+            write!(f, ": {}", self.detail)?;
+        }
+        if self.breaking {
+            write!(f, " (breaking)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a schema is read by the server or by the client, which decides what breaks
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+/// Collects the changes of one operation
+struct OperationDiff<'a> {
+    method: &'a str,
+    path: &'a str,
+    old_components: &'a Option<OpenApi3Components>,
+    new_components: &'a Option<OpenApi3Components>,
+    changes: Vec<Change>,
+}
+
+impl OperationDiff<'_> {
+    fn push(&mut self, kind: ChangeKind, detail: String, breaking: bool) {
+        self.changes.push(Change {
+            kind,
+            method: self.method.to_owned(),
+            path: self.path.to_owned(),
+            detail,
+            breaking,
+        });
+    }
+
+    fn parameters(&mut self, old: &BTreeMap<(String, String), &OpenApi3Parameter>, new: &BTreeMap<(String, String), &OpenApi3Parameter>) {
+        for (key, before) in old {
+            let target = format!("{} parameter `{}`", key.0, key.1);
+            match new.get(key) {
+                None => self.push(ChangeKind::Removed, format!("{} removed", target), true),
+                Some(after) => {
+                    if !before.required && after.required {
+                        self.push(ChangeKind::Changed, format!("{} became required", target), true);
+                    } else if before.required && !after.required {
+                        self.push(ChangeKind::Changed, format!("{} became optional", target), false);
+                    }
+                    if let (Some(before), Some(after)) = (before.get_schema(), after.get_schema()) {
+                        self.schema(&target, before, after, Direction::Request, &mut BTreeSet::new());
+                    }
+                },
+            }
+        }
+        for (key, after) in new {
+            if !old.contains_key(key) {
+                let detail = format!("{} parameter `{}` added{}", key.0, key.1, if after.required { " as required" } else { "" });
+                self.push(ChangeKind::Added, detail, after.required);
+            }
+        }
+    }
+
+    /// Compares two schemas, following the references into each document's components. A reference `new`
+    /// cannot resolve is reported as breaking, one of `old` was already broken and is skipped.
+    fn schema(&mut self, target: &str, old: &OpenApi3Schema, new: &OpenApi3Schema, direction: Direction, seen: &mut BTreeSet<String>) {
+        if let (OpenApi3Schema::RefType(before), OpenApi3Schema::RefType(after)) = (old, new) {
+            // a pair of named schemas is compared once, which also ends recursive schemas
+            if !seen.insert(format!("{} {}", before._ref, after._ref)) {
+                return;
+            }
+        }
+        let new = match (resolve_schema(new, self.new_components), new) {
+            (Some(new), _) => new,
+            (None, OpenApi3Schema::RefType(after)) => {
+                self.push(ChangeKind::Changed, format!("{}: `{}` cannot be resolved", target, after._ref), true);
+                return;
+            },
+            (None, _) => return,
+        };
+        let old = match resolve_schema(old, self.old_components) {
+            Some(old) => old,
+            None => return,
+        };
+        let (old_type, new_type) = (schema_type(old), schema_type(new));
+        if old_type != new_type {
+            // every integer is a number, so widening keeps the requests valid and narrowing the responses
+            let breaking = match (old_type, new_type) {
+                ("integer", "number") => direction == Direction::Response,
+                ("number", "integer") => direction == Direction::Request,
+                _ => true,
+            };
+            self.push(ChangeKind::Changed, format!("{}: type changed from {} to {}", target, old_type, new_type), breaking);
+            return;
+        }
+        if let (Some(before), Some(after)) = (object_shape(old), object_shape(new)) {
+            self.object(target, before, after, direction, seen);
+            return;
+        }
+        match (old, new) {
+            (OpenApi3Schema::ArrayType(before), OpenApi3Schema::ArrayType(after)) => {
+                self.schema(&format!("{}[]", target), &before.items, &after.items, direction, seen);
+            },
+            (OpenApi3Schema::StringType(before), OpenApi3Schema::StringType(after)) => {
+                let old_values: BTreeSet<&String> = before._enum.iter().flatten().collect();
+                let new_values: BTreeSet<&String> = after._enum.iter().flatten().collect();
+                // a new enum restricts what was free text, dropping the enum lifts the restriction
+                if old_values.is_empty() != new_values.is_empty() {
+                    let restricted = old_values.is_empty();
+                    let detail = format!("{}: enum {}", target, if restricted { "added" } else { "removed" });
+                    self.push(ChangeKind::Changed, detail, restricted == (direction == Direction::Request));
+                    return;
+                }
+                for value in old_values.difference(&new_values) {
+                    self.push(ChangeKind::Removed, format!("{}: enum value `{}` removed", target, value), direction == Direction::Request);
+                }
+                for value in new_values.difference(&old_values) {
+                    self.push(ChangeKind::Added, format!("{}: enum value `{}` added", target, value), direction == Direction::Response);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Compares the properties and `required` lists of two object schemas
+    fn object(&mut self, target: &str, old: ObjectShape, new: ObjectShape, direction: Direction, seen: &mut BTreeSet<String>) {
+        let ((old_properties, old_required), (new_properties, new_required)) = (old, new);
+        for (name, property) in &old_properties {
+            let property_target = format!("{}: property `{}`", target, name);
+            match new_properties.get(name) {
+                None => self.push(ChangeKind::Removed, format!("{} removed", property_target), direction == Direction::Response),
+                Some(after_property) => {
+                    if direction == Direction::Request && !old_required.contains(name) && new_required.contains(name) {
+                        self.push(ChangeKind::Changed, format!("{} became required", property_target), true);
+                    } else if direction == Direction::Response && old_required.contains(name) && !new_required.contains(name) {
+                        self.push(ChangeKind::Changed, format!("{} became optional", property_target), true);
+                    }
+                    self.schema(&property_target, property, after_property, direction, seen);
+                },
+            }
+        }
+        for name in new_properties.keys().filter(|name| !old_properties.contains_key(*name)) {
+            let required = direction == Direction::Request && new_required.contains(name);
+            let detail = format!("{}: property `{}` added{}", target, name, if required { " as required" } else { "" });
+            self.push(ChangeKind::Added, detail, required);
+        }
+    }
+
+    fn request_body(&mut self, old: &OpenApi3OperationMethod, new: &OpenApi3OperationMethod) {
+        match (&old.request_body, &new.request_body) {
+            (None, Some(_)) => self.push(ChangeKind::Added, String::from("request body added"), false),
+            (Some(_), None) => self.push(ChangeKind::Removed, String::from("request body removed"), true),
+            (Some(before), Some(after)) => {
+                for (media_type, media) in &before.content {
+                    let target = format!("request body {}", media_type);
+                    match after.content.get(media_type) {
+                        None => self.push(ChangeKind::Removed, format!("{} removed", target), true),
+                        Some(after_media) => {
+                            if let (Some(before), Some(after)) = (&media.schema, &after_media.schema) {
+                                self.schema(&target, before, after, Direction::Request, &mut BTreeSet::new());
+                            }
+                        },
+                    }
+                }
+                for media_type in after.content.keys().filter(|media_type| !before.content.contains_key(*media_type)) {
+                    self.push(ChangeKind::Added, format!("request body {} added", media_type), false);
+                }
+            },
+            (None, None) => {},
+        }
+    }
+
+    fn responses(&mut self, old: &OpenApi3OperationMethod, new: &OpenApi3OperationMethod) {
+        for (status, response) in &old.responses {
+            let target = format!("response {}", status);
+            match new.responses.get(status) {
+                None => self.push(ChangeKind::Removed, format!("{} removed", target), true),
+                Some(after) => {
+                    let empty = BTreeMap::new();
+                    let old_content = response.content.as_ref().unwrap_or(&empty);
+                    let new_content = after.content.as_ref().unwrap_or(&empty);
+                    for (media_type, media) in old_content {
+                        let media_target = format!("{} {}", target, media_type);
+                        match new_content.get(media_type) {
+                            None => self.push(ChangeKind::Removed, format!("{} removed", media_target), true),
+                            Some(after_media) => {
+                                if let (Some(before), Some(after)) = (&media.schema, &after_media.schema) {
+                                    self.schema(&media_target, before, after, Direction::Response, &mut BTreeSet::new());
+                                }
+                            },
+                        }
+                    }
+                    for media_type in new_content.keys().filter(|media_type| !old_content.contains_key(*media_type)) {
+                        self.push(ChangeKind::Added, format!("{} {} added", target, media_type), false);
+                    }
+                },
+            }
+        }
+        for status in new.responses.keys().filter(|status| !old.responses.contains_key(*status)) {
+            self.push(ChangeKind::Added, format!("response {} added", status), false);
+        }
+    }
+
+    /// Each alternative of the effective `security` is a set of schemes, no security at all being the empty
+    /// alternative. Dropping an alternative breaks the clients using it.
+    fn security(&mut self, old: &OpenApi3OperationMethodSecurity, new: &OpenApi3OperationMethodSecurity) {
+        let old_alternatives = security_alternatives(old);
+        let new_alternatives = security_alternatives(new);
+        for alternative in old_alternatives.difference(&new_alternatives) {
+            self.push(ChangeKind::Removed, format!("security {} removed", alternative), true);
+        }
+        for alternative in new_alternatives.difference(&old_alternatives) {
+            self.push(ChangeKind::Added, format!("security {} added", alternative), false);
+        }
+    }
+}
+
+fn resolve_schema<'a>(schema: &'a OpenApi3Schema, components: &'a Option<OpenApi3Components>) -> Option<&'a OpenApi3Schema> {
+    match schema {
+        OpenApi3Schema::RefType(reference) => {
+            let name = reference._ref.trim_start_matches("#/components/schemas/");
+            components.as_ref()
+                .and_then(|c| c.schemas.as_ref())
+                .and_then(|schemas| schemas.get(name))
+        },
+        schema => Some(schema),
+    }
+}
+
+fn schema_type(schema: &OpenApi3Schema) -> &str {
+    match schema {
+        OpenApi3Schema::ArrayType(_) => "array",
+        OpenApi3Schema::ObjectType(_) => "object",
+        OpenApi3Schema::StringType(s) => &s._type,
+        OpenApi3Schema::IntegerType(s) => &s._type,
+        OpenApi3Schema::NumberType(s) => &s._type,
+        OpenApi3Schema::BooleanType(s) => &s._type,
+        OpenApi3Schema::RefType(s) => &s._ref,
+        OpenApi3Schema::Raw(raw) => raw.get("type").and_then(Value::as_str)
+            .or_else(|| ["allOf", "oneOf", "anyOf"].iter().copied().find(|key| raw.get(*key).is_some()))
+            .unwrap_or("schema"),
+    }
+}
+
+/// The properties of an object schema and the names it requires
+type ObjectShape = (BTreeMap<String, OpenApi3Schema>, BTreeSet<String>);
+
+/// The shape of an object schema, which the model keeps raw when it has no `properties`
+fn object_shape(schema: &OpenApi3Schema) -> Option<ObjectShape> {
+    match schema {
+        OpenApi3Schema::ObjectType(object) => Some((
+            object.properties.iter().map(|(name, property)| (name.clone(), (**property).clone())).collect(),
+            required_properties(object.extensions.get("required")),
+        )),
+        OpenApi3Schema::Raw(raw) if raw.get("type").and_then(Value::as_str) == Some("object") => Some((
+            raw.get("properties").and_then(|properties| serde_json::from_value(properties.clone()).ok()).unwrap_or_default(),
+            required_properties(raw.get("required")),
+        )),
+        _ => None,
+    }
+}
+
+/// The names of a `required` list, which the model keeps with the unknown keys of an object schema
+fn required_properties(required: Option<&Value>) -> BTreeSet<String> {
+    required.and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+/// The root `security` of a document, which the model keeps with the unknown keys
+fn root_security(openapi: &OpenApi3) -> OpenApi3OperationMethodSecurity {
+    openapi.extensions.get("security").and_then(|security| serde_json::from_value(security.clone()).ok())
+}
+
+/// The security an operation is called with, its own or else the one of the document
+fn effective_security<'a>(operation: &'a OpenApi3OperationMethod, root: &'a OpenApi3OperationMethodSecurity) -> &'a OpenApi3OperationMethodSecurity {
+    if operation.security.is_some() {
+        &operation.security
+    } else {
+        root
+    }
+}
+
+fn security_alternatives(security: &OpenApi3OperationMethodSecurity) -> BTreeSet<String> {
+    let alternatives: BTreeSet<String> = security.iter().flatten()
+        .map(|requirement| requirement.keys().map(|scheme| format!("`{}`", scheme)).collect::<Vec<String>>().join(" + "))
+        .map(|alternative| if alternative.is_empty() { String::from("none") } else { alternative })
+        .collect();
+    if alternatives.is_empty() {
+        std::iter::once(String::from("none")).collect()
+    } else {
+        alternatives
+    }
+}
+
+/// Path item parameters overridden by the operation ones, by location and name
+fn parameters<'a>(path_item: &'a OpenApi3PathItem, operation: &'a OpenApi3OperationMethod, components: &'a Option<OpenApi3Components>) -> BTreeMap<(String, String), &'a OpenApi3Parameter> {
+    path_item.parameters.iter().chain(operation.parameters.iter())
+        .filter_map(|parameter| parameter.resolve(components))
+        .map(|parameter| ((parameter._in.clone(), parameter.name.clone()), parameter))
+        .collect()
+}
+
+/// Operations added or removed from `old` to `new`, and for the operations in both the parameters,
+/// request bodies, responses and security that changed, ordered by path then method. An operation edited
+/// in a way none of those cover, its summary say, is listed as changed on its own.
+pub fn diff_operations(old: &OpenApi3, new: &OpenApi3) -> Vec<Change> {
+    let mut changes: Vec<Change> = Vec::new();
+    let (old_security, new_security) = (root_security(old), root_security(new));
+    let mut paths: Vec<&String> = old.paths.keys().chain(new.paths.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    for path in paths {
+        let old_operations = old.paths.get(path).map(|item| item.operations()).unwrap_or_default();
+        let new_operations = new.paths.get(path).map(|item| item.operations()).unwrap_or_default();
+        let mut methods: Vec<&str> = old_operations.iter().chain(new_operations.iter()).map(|(method, _)| *method).collect();
+        methods.sort_unstable();
+        methods.dedup();
+        for method in methods {
+            let before = old_operations.iter().find(|(m, _)| *m == method).map(|(_, operation)| *operation);
+            let after = new_operations.iter().find(|(m, _)| *m == method).map(|(_, operation)| *operation);
+            let mut diff = OperationDiff {
+                method,
+                path,
+                old_components: &old.components,
+                new_components: &new.components,
+                changes: Vec::new(),
+            };
+            match (before, after) {
+                (None, Some(_)) => diff.push(ChangeKind::Added, String::new(), false),
+                (Some(_), None) => diff.push(ChangeKind::Removed, String::new(), true),
+                (Some(before), Some(after)) => {
+                    diff.parameters(&parameters(&old.paths[path], before, &old.components), &parameters(&new.paths[path], after, &new.components));
+                    diff.request_body(before, after);
+                    diff.responses(before, after);
+                    diff.security(effective_security(before, &old_security), effective_security(after, &new_security));
+                    if diff.changes.is_empty() && serde_json::to_value(before).ok() != serde_json::to_value(after).ok() {
+                        diff.push(ChangeKind::Changed, String::new(), false);
+                    }
+                },
+                (None, None) => {},
+            }
+            changes.extend(diff.changes);
+        }
+    }
+    changes
+}
+
+/// One line per change, then the totals
+pub fn changes_text(changes: &[Change]) -> String {
+    let mut text: String = changes.iter().map(|change| format!("{}\n", change)).collect();
+    let breaking = changes.iter().filter(|change| change.breaking).count();
+    text.push_str(&format!("{} change(s), {} breaking\n", changes.len(), breaking));
+    text
+}
+
+/// The breaking changes then the others as Markdown lists, for a pull request comment
+pub fn changes_markdown(changes: &[Change]) -> String {
+    let mut markdown = String::from("# API changes\n");
+    for (title, breaking) in [("Breaking changes", true), ("Non-breaking changes", false)] {
+        let section: Vec<&Change> = changes.iter().filter(|change| change.breaking == breaking).collect();
+        markdown.push_str(&format!("\n## {} ({})\n\n", title, section.len()));
+        if section.is_empty() {
+            markdown.push_str("None.\n");
+        }
+        for change in section {
+            let kind = match change.kind {
+                ChangeKind::Added => "added",
+                ChangeKind::Removed => "removed",
+                ChangeKind::Changed => "changed",
+            };
+            markdown.push_str(&format!("- **{}** `{} {}`", kind, change.method.to_uppercase(), change.path));
+            if !change.detail.is_empty() {
+                markdown.push_str(&format!(": {}", change.detail));
+            }
+            markdown.push('\n');
+        }
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document(operation: Value, schemas: Value, security: Option<Value>) -> OpenApi3 {
+        let mut document = json!({
+            "openapi": "3.0.0",
+            "info": { "title": "Pets", "description": "", "version": "1.0.0" },
+            "servers": [],
+            "paths": { "/pets": { "post": operation } },
+            "components": { "schemas": schemas },
+        });
+        if let Some(security) = security {
+            document["security"] = security;
+        }
+        serde_json::from_value(document).unwrap()
+    }
+
+    /// An operation posting and returning `Pet`
+    fn operation(security: Option<Value>) -> Value {
+        let pet = json!({ "schema": { "$ref": "#/components/schemas/Pet" } });
+        let mut operation = json!({
+            "tags": ["pets"],
+            "parameters": [],
+            "requestBody": { "content": { "application/json": pet } },
+            "responses": { "200": { "description": "OK", "content": { "application/json": pet } } },
+        });
+        if let Some(security) = security {
+            operation["security"] = security;
+        }
+        operation
+    }
+
+    fn breaking(changes: &[Change]) -> Vec<(&str, bool)> {
+        changes.iter().map(|change| (change.detail.as_str(), change.breaking)).collect()
+    }
+
+    #[test]
+    fn enum_changes_break_the_side_reading_them() {
+        let pet = |values: Value| json!({ "Pet": { "type": "object", "properties": { "kind": { "type": "string", "enum": values } } } });
+        let old = document(operation(None), pet(json!(["cat", "dog"])), None);
+        let new = document(operation(None), pet(json!(["cat", "bird"])), None);
+        assert_eq!(breaking(&diff_operations(&old, &new)), vec![
+            ("request body application/json: property `kind`: enum value `dog` removed", true),
+            ("request body application/json: property `kind`: enum value `bird` added", false),
+            ("response 200 application/json: property `kind`: enum value `dog` removed", false),
+            ("response 200 application/json: property `kind`: enum value `bird` added", true),
+        ]);
+    }
+
+    #[test]
+    fn required_and_removed_properties_depend_on_the_direction() {
+        let old = document(operation(None), json!({ "Pet": { "type": "object", "properties": { "name": { "type": "string" }, "tag": { "type": "string" } }, "required": ["tag"] } }), None);
+        let new = document(operation(None), json!({ "Pet": { "type": "object", "properties": { "name": { "type": "string" } }, "required": ["name"] } }), None);
+        assert_eq!(breaking(&diff_operations(&old, &new)), vec![
+            ("request body application/json: property `name` became required", true),
+            ("request body application/json: property `tag` removed", false),
+            ("response 200 application/json: property `tag` removed", true),
+        ]);
+    }
+
+    #[test]
+    fn follows_recursive_refs_once() {
+        let node = |id_type: &str| json!({ "Pet": { "type": "object", "properties": {
+            "id": { "type": id_type },
+            "parent": { "$ref": "#/components/schemas/Pet" },
+            "children": { "type": "array", "items": { "$ref": "#/components/schemas/Pet" } },
+        } } });
+        let old = document(operation(None), node("integer"), None);
+        let new = document(operation(None), node("string"), None);
+        assert_eq!(breaking(&diff_operations(&old, &new)), vec![
+            ("request body application/json: property `id`: type changed from integer to string", true),
+            ("response 200 application/json: property `id`: type changed from integer to string", true),
+        ]);
+        assert!(diff_operations(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn compares_the_security_operations_inherit() {
+        let schemas = json!({ "Pet": { "type": "object", "properties": {} } });
+        let open = document(operation(None), schemas.clone(), None);
+        let root = document(operation(None), schemas.clone(), Some(json!([{ "apiKey": [] }])));
+        assert_eq!(breaking(&diff_operations(&open, &root)), vec![
+            ("security none removed", true),
+            ("security `apiKey` added", false),
+        ]);
+        // moving the requirement from the operation to the root leaves the operation as secured as it was
+        let own = document(operation(Some(json!([{ "apiKey": [] }]))), schemas, None);
+        assert!(diff_operations(&own, &root).iter().all(|change| !change.breaking));
+    }
+
+    #[test]
+    fn widening_an_integer_breaks_only_the_responses() {
+        let pet = |id_type: &str| json!({ "Pet": { "type": "object", "properties": { "id": { "type": id_type } } } });
+        let old = document(operation(None), pet("integer"), None);
+        let new = document(operation(None), pet("number"), None);
+        assert_eq!(breaking(&diff_operations(&old, &new)), vec![
+            ("request body application/json: property `id`: type changed from integer to number", false),
+            ("response 200 application/json: property `id`: type changed from integer to number", true),
+        ]);
+        assert_eq!(breaking(&diff_operations(&new, &old)), vec![
+            ("request body application/json: property `id`: type changed from number to integer", true),
+            ("response 200 application/json: property `id`: type changed from number to integer", false),
+        ]);
+    }
+
+    #[test]
+    fn compares_objects_without_properties() {
+        let old = document(operation(None), json!({ "Pet": { "type": "object", "properties": { "name": { "type": "string" } } } }), None);
+        let new = document(operation(None), json!({ "Pet": { "type": "object", "required": ["name"] } }), None);
+        assert_eq!(breaking(&diff_operations(&old, &new)), vec![
+            ("request body application/json: property `name` removed", false),
+            ("response 200 application/json: property `name` removed", true),
+        ]);
+    }
+
+    #[test]
+    fn reports_references_the_new_document_cannot_resolve() {
+        let old = document(operation(None), json!({ "Pet": { "type": "object", "properties": {} } }), None);
+        let new = document(operation(None), json!({}), None);
+        assert_eq!(breaking(&diff_operations(&old, &new)), vec![
+            ("request body application/json: `#/components/schemas/Pet` cannot be resolved", true),
+            ("response 200 application/json: `#/components/schemas/Pet` cannot be resolved", true),
+        ]);
+    }
+}
@@ -0,0 +1,65 @@
+use serde_json::Value;
+use crate::error::{from_json_str, from_json_value, Error, Result, Warning};
+use crate::lenient::from_value_lenient;
+use crate::model::OpenApi3;
+use crate::postman_legacy_model::{read_postman_collection, read_postman_collection_lenient, PostmanVersion};
+use crate::postman_model::PostmanCollection2c1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DocumentKind {
+    OpenApi3,
+    Swagger2,
+    Postman(PostmanVersion),
+}
+
+impl DocumentKind {
+    /// Kind named by `openapi`, `swagger` or `info.schema`, falling back to the shape of a v1 collection
+    pub fn detect(document: &Value) -> Option<DocumentKind> {
+        if document.get("openapi").is_some() {
+            Some(DocumentKind::OpenApi3)
+        } else if document.get("swagger").is_some() {
+            Some(DocumentKind::Swagger2)
+        } else if document.pointer("/info/schema").and_then(Value::as_str).is_some_and(|schema| schema.contains("getpostman.com")) {
+            Some(DocumentKind::Postman(PostmanVersion::detect(document)))
+        } else if document.get("info").is_none() && (document.get("requests").is_some() || document.get("order").is_some()) {
+            Some(DocumentKind::Postman(PostmanVersion::V1))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum Document {
+    OpenApi3(OpenApi3),
+    Postman(PostmanCollection2c1),
+}
+
+/// Parses an OpenAPI 3 document, dropping the elements that fail to parse
+pub fn parse_openapi3_lenient(json: &str, source: &str) -> Result<(OpenApi3, Vec<Warning>)> {
+    let value: Value = from_json_str(json, source)?;
+    let mut warnings: Vec<Warning> = Vec::new();
+    let openapi = from_value_lenient(value, source, &mut warnings)?;
+    Ok((openapi, warnings))
+}
+
+/// Parses an OpenAPI 3 document or a postman collection of any version, whichever `json` holds
+pub fn parse_document(json: &str, source: &str, lenient: bool) -> Result<(Document, Vec<Warning>)> {
+    let value: Value = from_json_str(json, source)?;
+    match DocumentKind::detect(&value) {
+        Some(DocumentKind::OpenApi3) if lenient => parse_openapi3_lenient(json, source).map(|(openapi, warnings)| (Document::OpenApi3(openapi), warnings)),
+        Some(DocumentKind::OpenApi3) => Ok((Document::OpenApi3(from_json_value(value, source)?), Vec::new())),
+        Some(DocumentKind::Postman(_)) if lenient => read_postman_collection_lenient(json, source).map(|(collection, warnings)| (Document::Postman(collection), warnings)),
+        Some(DocumentKind::Postman(_)) => Ok((Document::Postman(read_postman_collection(json, source)?), Vec::new())),
+        Some(DocumentKind::Swagger2) => Err(Error::Unsupported {
+            pointer: String::from("/swagger"),
+            message: format!("swagger 2.0 document {}, convert it to OpenAPI 3 first", source),
+        }),
+        None => Err(Error::Parse {
+            source: source.to_owned(),
+            pointer: String::new(),
+            message: String::from("neither an OpenAPI document (`openapi`) nor a postman collection (`info.schema`)"),
+        }),
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+use serde::de::DeserializeOwned;
+
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: String,
+        cause: std::io::Error,
+    },
+    /// The input is not valid JSON or does not match the model, `pointer` is where deserializing stopped
+    Parse {
+        source: String,
+        pointer: String,
+        message: String,
+    },
+    Unsupported {
+        pointer: String,
+        message: String,
+    },
+    InvalidReference {
+        pointer: String,
+        reference: String,
+    },
+    Invalid {
+        pointer: String,
+        message: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, cause } => write!(f, "cannot read or write {}: {}", path, cause),
+            Error::Parse { source, pointer, message } => write!(f, "cannot parse {}#{}: {}", source, pointer, message),
+            Error::Unsupported { pointer, message } => write!(f, "unsupported {} at {}", message, pointer),
+            Error::InvalidReference { pointer, reference } => write!(f, "cannot resolve {} at {}", reference, pointer),
+            Error::Invalid { pointer, message } if pointer.is_empty() => write!(f, "{}", message),
+            Error::Invalid { pointer, message } => write!(f, "{} at {}", message, pointer),
+        }
+    }
+}
+
+/// `failure::Fail` comes with it through failure's blanket implementation
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { cause, .. } => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    pub fn io(path: &str, cause: std::io::Error) -> Error {
+        Error::Io {
+            path: path.to_owned(),
+            cause,
+        }
+    }
+    /// JSON pointer of the offending element, `None` for IO errors
+    pub fn pointer(&self) -> Option<&str> {
+        match self {
+            Error::Io { .. } => None,
+            Error::Parse { pointer, .. } | Error::Unsupported { pointer, .. } | Error::InvalidReference { pointer, .. } | Error::Invalid { pointer, .. } => Some(pointer),
+        }
+    }
+}
+
+/// Escapes one reference token of a JSON pointer (RFC 6901)
+pub fn pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+pub fn pointer(tokens: &[&str]) -> String {
+    tokens.iter().map(|token| format!("/{}", pointer_token(token))).collect()
+}
+
+pub(crate) fn path_to_pointer(path: &serde_path_to_error::Path) -> String {
+    let mut pointer = String::new();
+    for segment in path.iter() {
+        match segment {
+            serde_path_to_error::Segment::Seq { index } => pointer.push_str(&format!("/{}", index)),
+            serde_path_to_error::Segment::Map { key } => pointer.push_str(&format!("/{}", pointer_token(key))),
+            serde_path_to_error::Segment::Enum { .. } | serde_path_to_error::Segment::Unknown => {},
+        }
+    }
+    pointer
+}
+
+/// Deserializes `json`, reporting where it failed; `source` names the input in the message
+pub fn from_json_str<T: DeserializeOwned>(json: &str, source: &str) -> Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(|err| Error::Parse {
+        source: source.to_owned(),
+        pointer: path_to_pointer(err.path()),
+        message: err.inner().to_string(),
+    })
+}
+
+pub fn from_json_value<T: DeserializeOwned>(value: serde_json::Value, source: &str) -> Result<T> {
+    serde_path_to_error::deserialize(value).map_err(|err| Error::Parse {
+        source: source.to_owned(),
+        pointer: path_to_pointer(err.path()),
+        message: err.inner().to_string(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningKind {
+    /// The element was left out of the output
+    Dropped,
+    /// The element was converted into something close to it
+    Approximated,
+    /// Several elements were combined into one
+    Merged,
+    /// Inputs that should not overlap define the same element, which was merged
+    Conflict,
+}
+
+/// Something the conversion did not carry over as is, `pointer` locates it in the input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Warning {
+    /// The input `pointer` is in, left out when the conversion read a single one
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub pointer: String,
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, pointer: &str, message: String) -> Warning {
+        Warning {
+            source: None,
+            pointer: pointer.to_owned(),
+            kind,
+            message,
+        }
+    }
+    pub fn with_source(mut self, source: Option<&str>) -> Warning {
+        self.source = source.map(str::to_owned);
+        self
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{} ({}#{})", self.message, source, self.pointer),
+            None => write!(f, "{} ({})", self.message, self.pointer),
+        }
+    }
+}
+
+/// In lenient mode records `err` as a warning of `kind` and carries on, otherwise fails with it
+pub fn tolerate(err: Error, kind: WarningKind, lenient: bool, warnings: &mut Vec<Warning>) -> Result<()> {
+    if lenient {
+        warnings.push(Warning::new(kind, err.pointer().unwrap_or(""), err.to_string()));
+        Ok(())
+    } else {
+        Err(err)
+    }
+}
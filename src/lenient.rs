@@ -0,0 +1,62 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use crate::error::{path_to_pointer, Error, Result, Warning, WarningKind};
+
+/// Elements dropped before giving up, as every round parses the whole input again
+const MAX_REPAIRS: usize = 64;
+
+fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Removes the element at `pointer`, `false` when it is the root or does not exist
+fn remove_pointer(value: &mut Value, pointer: &str) -> bool {
+    let index = match pointer.rfind('/') {
+        Some(index) => index,
+        None => return false,
+    };
+    let token = unescape_token(&pointer[index + 1..]);
+    match value.pointer_mut(&pointer[..index]) {
+        Some(Value::Object(obj)) => obj.remove(&token).is_some(),
+        Some(Value::Array(list)) => match token.parse::<usize>() {
+            Ok(i) if i < list.len() => {
+                list.remove(i);
+                true
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Deserializes `value`, dropping whatever element fails to parse until the rest does. Dropping a required
+/// field makes its parent fail on the next round, so the repair climbs up to the nearest optional element.
+/// Schemas and auths of a shape the model does not read are kept raw, so few elements need dropping and the
+/// input is reported as not parsing past `MAX_REPAIRS` of them.
+pub fn from_value_lenient<T: DeserializeOwned>(mut value: Value, source: &str, warnings: &mut Vec<Warning>) -> Result<T> {
+    let mut repairs = 0;
+    loop {
+        let err = match serde_path_to_error::deserialize(&value) {
+            Ok(parsed) => return Ok(parsed),
+            Err(err) => err,
+        };
+        let pointer = path_to_pointer(err.path());
+        let message = err.inner().to_string();
+        if repairs == MAX_REPAIRS {
+            return Err(Error::Parse {
+                source: source.to_owned(),
+                pointer,
+                message: format!("{}, giving up after dropping {} elements", message, MAX_REPAIRS),
+            });
+        }
+        if !remove_pointer(&mut value, &pointer) {
+            return Err(Error::Parse {
+                source: source.to_owned(),
+                pointer,
+                message,
+            });
+        }
+        warnings.push(Warning::new(WarningKind::Dropped, &pointer, message));
+        repairs += 1;
+    }
+}
@@ -0,0 +1,133 @@
+//! Converts between OpenAPI 3 documents and Postman collections.
+//!
+//! `to_postman_colletion_2c1` turns an `OpenApi3` into a v2.1 collection and `to_openapi3` goes the other way,
+//! both driven by an option struct that can be deserialized from a config file or built in code.
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate failure;
+
+pub mod batch;
+pub mod config;
+pub mod diff;
+pub mod document;
+pub mod error;
+pub mod lenient;
+pub mod merge;
+pub mod model;
+pub mod parameter_style;
+pub mod postman_model;
+pub mod postman_legacy_model;
+pub mod split;
+pub mod sync;
+
+use std::io::{Read, Write};
+use std::path::Path;
+use serde::Serialize;
+
+pub use config::config_schema;
+pub use diff::{changes_markdown, changes_text, diff_operations, Change, ChangeKind};
+pub use document::{parse_document, Document, DocumentKind};
+pub use error::{Error, Result, Warning, WarningKind};
+pub use merge::{merge_to_postman, read_postman_merge, PostmanMergeOption};
+pub use model::{merge_to_openapi3, to_openapi3, OpenApi3, OpenApi3ConvertOption};
+pub use postman_model::{to_postman_colletion_2c1, PostmanCollection2c1, PostmanConvertOption};
+pub use split::{postman_environment, split_to_postman_collections, PostmanSplit};
+pub use sync::{sync_postman_collection, SyncSummary};
+pub use postman_legacy_model::{read_postman_collection, read_postman_collection_lenient, to_postman_collection_2c0, PostmanCollection2c0, PostmanVersion};
+
+/// Path given on the command line for stdin or stdout
+pub const STDIO: &str = "-";
+
+fn is_stdio(path: &Path) -> bool {
+    path == Path::new(STDIO)
+}
+
+pub(crate) fn source_name(path: &Path) -> String {
+    if is_stdio(path) {
+        String::from("<stdin>")
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// Reads the whole file, or stdin when `path` is `-`
+pub(crate) fn read_file(path: &Path) -> Result<String> {
+    if is_stdio(path) {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input).map_err(|err| Error::io(&source_name(path), err))?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(path).map_err(|err| Error::io(&source_name(path), err))
+    }
+}
+
+/// Reads a JSON OpenAPI 3 document
+pub fn read_openapi3(path: impl AsRef<Path>) -> Result<OpenApi3> {
+    let path = path.as_ref();
+    error::from_json_str(&read_file(path)?, &source_name(path))
+}
+
+/// Reads a JSON OpenAPI 3 document, dropping the elements that fail to parse
+pub fn read_openapi3_lenient(path: impl AsRef<Path>) -> Result<(OpenApi3, Vec<Warning>)> {
+    let path = path.as_ref();
+    document::parse_openapi3_lenient(&read_file(path)?, &source_name(path))
+}
+
+/// Reads a v1, v2.0 or v2.1 collection file into the v2.1 model, dropping the elements that fail to parse
+pub fn read_postman_collection_file_lenient(path: impl AsRef<Path>) -> Result<(PostmanCollection2c1, Vec<Warning>)> {
+    let path = path.as_ref();
+    read_postman_collection_lenient(&read_file(path)?, &source_name(path))
+}
+
+/// Reads a v1, v2.0 or v2.1 collection file into the v2.1 model
+pub fn read_postman_collection_file(path: impl AsRef<Path>) -> Result<PostmanCollection2c1> {
+    let path = path.as_ref();
+    read_postman_collection(&read_file(path)?, &source_name(path))
+}
+
+/// Reads an OpenAPI 3 document or a postman collection, telling them apart by their content
+pub fn read_document(path: impl AsRef<Path>, lenient: bool) -> Result<(Document, Vec<Warning>)> {
+    let path = path.as_ref();
+    parse_document(&read_file(path)?, &source_name(path), lenient)
+}
+
+/// Reads a JSON, YAML or TOML config into one of the option structs
+pub fn read_option<T: serde::de::DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
+    config::read_config(path.as_ref())
+}
+
+fn write_to<W: Write, T: Serialize>(mut writer: W, value: &T, pretty: bool) -> serde_json::Result<()> {
+    if pretty {
+        serde_json::to_writer_pretty(&mut writer, value)?;
+    } else {
+        serde_json::to_writer(&mut writer, value)?;
+    }
+    writer.flush().map_err(serde_json::Error::io)
+}
+
+/// Writes `value` as JSON, indented when `pretty` is set, streamed into the file or into stdout when `path` is `-`
+pub fn write_json<T: Serialize>(path: impl AsRef<Path>, value: &T, pretty: bool) -> Result<()> {
+    let path = path.as_ref();
+    let name = if is_stdio(path) { String::from("<stdout>") } else { path.display().to_string() };
+    let written = if is_stdio(path) {
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        write_to(&mut stdout, value, pretty).and_then(|_| writeln!(stdout).map_err(serde_json::Error::io))
+    } else {
+        let file = std::fs::File::create(path).map_err(|err| Error::io(&name, err))?;
+        write_to(std::io::BufWriter::new(file), value, pretty)
+    };
+    match written {
+        // the reader of a pipe stopped early, as `head` does, which is not the writer's failure
+        Err(ref err) if is_stdio(path) && err.io_error_kind() == Some(std::io::ErrorKind::BrokenPipe) => return Ok(()),
+        _ => {},
+    }
+    written.map_err(|err| match err.io_error_kind() {
+        Some(kind) => Error::io(&name, std::io::Error::new(kind, err.to_string())),
+        None => Error::Invalid {
+            pointer: String::new(),
+            message: err.to_string(),
+        },
+    })
+}
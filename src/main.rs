@@ -1,56 +1,548 @@
-use quicli::prelude::*;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
-#[macro_use]
-extern crate serde_derive;
-extern crate serde;
-extern crate failure;
 
-mod model;
-mod postman_model;
-
-use model::{OpenApi3, to_openapi3, OpenApi3ConvertOption};
-use postman_model::{PostmanCollection2c1, to_postman_colletion_2c1, PostmanConvertOption};
+use openapi3_x_postman::batch::{find_inputs, run_batch, sibling_config, summary_table, BatchStatus};
+use openapi3_x_postman::{changes_markdown, changes_text, config_schema, diff_operations, merge_to_openapi3, postman_environment, read_document, read_openapi3, read_openapi3_lenient, read_option, read_postman_collection_file, read_postman_collection_file_lenient, read_postman_merge, split_to_postman_collections, sync_postman_collection, to_openapi3, to_postman_collection_2c0, to_postman_colletion_2c1, write_json};
+use openapi3_x_postman::{Document, Error, OpenApi3, OpenApi3ConvertOption, PostmanCollection2c1, PostmanConvertOption, PostmanMergeOption, PostmanSplit, Result, Warning, STDIO};
 
 #[derive(Debug, StructOpt)]
-struct Cli {
-    /// Input file to read
+struct ConvertArgs {
+    /// Input file to read, `-` for stdin
+    #[structopt(default_value = "-")]
     file: String,
-    #[structopt(long="pts")]
-    postman_to_swagger: bool,
-    #[structopt(long="stp")]
-    swagger_to_postman: bool,
-    #[structopt(short = "o", long = "output")]
+    /// Output file to write, `-` for stdout
+    #[structopt(short = "o", long = "output", default_value = "-")]
     output: String,
-    #[structopt(short ="c", long = "config_file")]
-    config_file: Option<String>,
-}
-
-fn main() -> CliResult {
-    let args = Cli::from_args();
-    let json: String = std::fs::read_to_string(&args.file).unwrap();
-    if args.swagger_to_postman {
-        let openapi: OpenApi3 = serde_json::from_str(&json)?;
-        let postman_convert_option: PostmanConvertOption = if args.config_file.is_some() {
-            let option: String = std::fs::read_to_string(&args.config_file.unwrap()).unwrap();
-            serde_json::from_str(&option)?
-        } else {
-            PostmanConvertOption::default()
-        };
-        let postman_collection = to_postman_colletion_2c1(openapi, postman_convert_option);
-        let postman_str: String = serde_json::to_string(&postman_collection)?;
-        std::fs::write(args.output, postman_str)?;
-    } else if args.postman_to_swagger {
-        let pman: PostmanCollection2c1 = serde_json::from_str(&json)?;
-        let openapi3_convert_option: OpenApi3ConvertOption = if args.config_file.is_some() {
-            let option: String = std::fs::read_to_string(&args.config_file.unwrap()).unwrap();
-            serde_json::from_str(&option)?
-        } else {
-            OpenApi3ConvertOption::default()
-        };
-        let swagger_json = to_openapi3(pman.clone(), openapi3_convert_option);
-        // println!("{:?}", pman);
-        let swagger_str: String = serde_json::to_string_pretty(&swagger_json)?;
-        std::fs::write(args.output, swagger_str)?;
+    /// Drop or approximate what cannot be converted instead of failing
+    #[structopt(long = "lenient")]
+    lenient: bool,
+    /// Write the conversion warnings to this file as JSON
+    #[structopt(long = "warnings-report")]
+    warnings_report: Option<String>,
+}
+
+fn parse_key_value(pair: &str) -> std::result::Result<(String, String), String> {
+    match pair.find('=') {
+        Some(index) => Ok((pair[..index].to_owned(), pair[index + 1..].to_owned())),
+        None => Err(format!("expected KEY=VALUE, got `{}`", pair)),
+    }
+}
+
+/// Whether the variable raises a flag, clap only reads the environment for valued options
+fn env_flag(name: &str) -> bool {
+    match std::env::var(name) {
+        Ok(value) => !matches!(value.trim().to_lowercase().as_str(), "" | "0" | "false" | "no"),
+        Err(_) => false,
+    }
+}
+
+fn parse_split(split: &str) -> std::result::Result<PostmanSplit, String> {
+    match split {
+        "tag" => Ok(PostmanSplit::Tag),
+        "path-segment" => Ok(PostmanSplit::PathSegment),
+        _ => Err(format!("expected tag or path-segment, got `{}`", split)),
+    }
+}
+
+// Postman options layered on the config file, flags win over environment variables
+#[derive(Debug, StructOpt)]
+struct PostmanOverrides {
+    /// Host of every request url, usually a `{{HOST}}` variable
+    #[structopt(long = "host", env = "OPENAPI3_X_POSTMAN_HOST")]
+    host: Option<String>,
+    /// Path put before every request path
+    #[structopt(long = "preceding-path", env = "OPENAPI3_X_POSTMAN_PRECEDING_PATH")]
+    preceding_path: Option<String>,
+    /// Text put before every folder name
+    #[structopt(long = "prepend-tag", env = "OPENAPI3_X_POSTMAN_PREPEND_TAG")]
+    prepend_tag: Option<String>,
+    /// Value of every header named KEY, repeatable, one per line in the environment
+    #[structopt(long = "map-header", value_name = "KEY=VALUE", env = "OPENAPI3_X_POSTMAN_MAP_HEADER", value_delimiter = "\n", number_of_values = 1, parse(try_from_str = parse_key_value))]
+    map_header: Vec<(String, String)>,
+    /// Emit a test script per request asserting the documented responses [env: OPENAPI3_X_POSTMAN_GENERATE_TESTS]
+    #[structopt(long = "generate-tests")]
+    generate_tests: bool,
+    /// Embed the document so `to-openapi` can restore it unchanged [env: OPENAPI3_X_POSTMAN_ROUND_TRIP]
+    #[structopt(long = "round-trip")]
+    round_trip: bool,
+    /// Write one collection per tag or per first path segment into the `-o` directory
+    #[structopt(long = "split", env = "OPENAPI3_X_POSTMAN_SPLIT", possible_values = &["tag", "path-segment"], parse(try_from_str = parse_split))]
+    split: Option<PostmanSplit>,
+    /// Also write a postman environment declaring the variables the requests use
+    #[structopt(long = "environment", value_name = "FILE")]
+    environment: Option<String>,
+}
+
+impl PostmanOverrides {
+    fn apply(&self, mut option: PostmanConvertOption) -> PostmanConvertOption {
+        if let Some(host) = &self.host {
+            option = option.with_host(host);
+        }
+        if let Some(preceding_path) = &self.preceding_path {
+            option = option.with_preceding_path(preceding_path);
+        }
+        if let Some(prepend_tag) = &self.prepend_tag {
+            option = option.with_prepend_tag(prepend_tag);
+        }
+        for (key, value) in &self.map_header {
+            option.map_header.retain(|map| &map.key != key);
+            option = option.with_map_header(key, value);
+        }
+        option.generate_tests |= self.generate_tests || env_flag("OPENAPI3_X_POSTMAN_GENERATE_TESTS");
+        option.round_trip |= self.round_trip || env_flag("OPENAPI3_X_POSTMAN_ROUND_TRIP");
+        if let Some(split) = self.split {
+            option = option.with_split(split);
+        }
+        option
+    }
+}
+
+// OpenAPI options layered on the config file, flags win over environment variables
+#[derive(Debug, StructOpt)]
+struct OpenApiOverrides {
+    /// Server url, repeatable, replaces the servers of the config
+    #[structopt(long = "server", value_name = "URL", env = "OPENAPI3_X_POSTMAN_SERVER", use_delimiter = true, number_of_values = 1)]
+    server: Vec<String>,
+    /// Replaces the postman value VAR by VALUE, repeatable, comma separated in the environment
+    #[structopt(long = "map", value_name = "VAR=VALUE", env = "OPENAPI3_X_POSTMAN_MAP", use_delimiter = true, number_of_values = 1, parse(try_from_str = parse_key_value))]
+    map: Vec<(String, String)>,
+    /// Header that becomes an apiKey security scheme, repeatable
+    #[structopt(long = "api-key-header", value_name = "NAME", env = "OPENAPI3_X_POSTMAN_API_KEY_HEADER", use_delimiter = true, number_of_values = 1)]
+    api_key_header: Vec<String>,
+    /// Header left out of the operations, repeatable
+    #[structopt(long = "ignore-header", value_name = "NAME", env = "OPENAPI3_X_POSTMAN_IGNORE_HEADER", use_delimiter = true, number_of_values = 1)]
+    ignore_header: Vec<String>,
+    /// Keep transport headers as header parameters [env: OPENAPI3_X_POSTMAN_KEEP_TRANSPORT_HEADERS]
+    #[structopt(long = "keep-transport-headers")]
+    keep_transport_headers: bool,
+}
+
+impl OpenApiOverrides {
+    fn apply(&self, mut option: OpenApi3ConvertOption) -> OpenApi3ConvertOption {
+        if !self.server.is_empty() {
+            option.servers.clear();
+        }
+        for url in &self.server {
+            option = option.with_server(url, "");
+        }
+        for (key, value) in &self.map {
+            option.mapping.retain(|map| &map.key != key);
+            option = option.with_mapping(key, value);
+        }
+        for name in &self.api_key_header {
+            option = option.with_api_key_header(name);
+        }
+        for name in &self.ignore_header {
+            option = option.with_ignored_header(name);
+        }
+        option.keep_transport_headers |= self.keep_transport_headers || env_flag("OPENAPI3_X_POSTMAN_KEEP_TRANSPORT_HEADERS");
+        option
+    }
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Converts between OpenAPI 3 documents and postman collections")]
+enum Cli {
+    /// Convert an OpenAPI 3 document into a postman collection
+    ToPostman {
+        #[structopt(flatten)]
+        args: ConvertArgs,
+        /// Config (JSON, YAML or TOML) holding the postman convert options
+        #[structopt(short = "c", long = "config")]
+        config_file: Option<String>,
+        #[structopt(flatten)]
+        overrides: PostmanOverrides,
+        /// Postman collection format to write
+        #[structopt(long = "postman-version", default_value = "2.1", possible_values = &["2.0", "2.1"])]
+        postman_version: String,
+    },
+    /// Convert a postman collection (v1, v2.0 or v2.1) into an OpenAPI 3 document
+    ToOpenapi {
+        #[structopt(flatten)]
+        args: ConvertArgs,
+        /// Config (JSON, YAML or TOML) holding the OpenAPI convert options
+        #[structopt(short = "c", long = "config")]
+        config_file: Option<String>,
+        #[structopt(flatten)]
+        overrides: OpenApiOverrides,
+    },
+    /// Convert either way, telling the direction from the input
+    Convert {
+        #[structopt(flatten)]
+        args: ConvertArgs,
+        /// Config used when the input is an OpenAPI document
+        #[structopt(long = "to-postman-config")]
+        to_postman_config: Option<String>,
+        /// Config used when the input is a postman collection
+        #[structopt(long = "to-openapi-config")]
+        to_openapi_config: Option<String>,
+        #[structopt(flatten)]
+        postman_overrides: PostmanOverrides,
+        #[structopt(flatten)]
+        openapi_overrides: OpenApiOverrides,
+        #[structopt(long = "postman-version", default_value = "2.1", possible_values = &["2.0", "2.1"])]
+        postman_version: String,
+    },
+    /// Check that a document or collection parses and converts
+    Validate {
+        #[structopt(default_value = "-")]
+        file: String,
+        #[structopt(long = "lenient")]
+        lenient: bool,
+    },
+    /// List what changed between two documents or collections, exiting with 2 on breaking changes
+    Diff {
+        old: String,
+        new: String,
+        #[structopt(long = "format", default_value = "text", possible_values = &["text", "markdown", "json"])]
+        format: String,
+    },
+    /// Convert every document and collection under a directory or matching a glob, in parallel
+    Batch {
+        /// Directory, or glob such as `services/*/openapi.json`
+        input: String,
+        /// Directory the conversions are written to, mirroring the input tree
+        #[structopt(short = "o", long = "output")]
+        output: String,
+        /// Config for the OpenAPI documents without a config of their own next to them
+        #[structopt(long = "to-postman-config")]
+        to_postman_config: Option<String>,
+        /// Config for the postman collections without a config of their own next to them
+        #[structopt(long = "to-openapi-config")]
+        to_openapi_config: Option<String>,
+        #[structopt(flatten)]
+        postman_overrides: PostmanOverrides,
+        #[structopt(flatten)]
+        openapi_overrides: OpenApiOverrides,
+        #[structopt(long = "postman-version", default_value = "2.1", possible_values = &["2.0", "2.1"])]
+        postman_version: String,
+        #[structopt(long = "lenient")]
+        lenient: bool,
+    },
+    /// Merge the OpenAPI documents listed in a merge config into one collection, a folder per document
+    MergeSpecs {
+        #[structopt(flatten)]
+        args: ConvertArgs,
+    },
+    /// Merge several postman collections into one OpenAPI document, reporting the operations they both define
+    MergeCollections {
+        /// Collections to read
+        #[structopt(required = true, min_values = 2)]
+        files: Vec<String>,
+        /// Output file to write, `-` for stdout
+        #[structopt(short = "o", long = "output", default_value = "-")]
+        output: String,
+        /// Config (JSON, YAML or TOML) holding the OpenAPI convert options
+        #[structopt(short = "c", long = "config")]
+        config_file: Option<String>,
+        #[structopt(flatten)]
+        overrides: OpenApiOverrides,
+        /// Title of the document, the collection names joined by default
+        #[structopt(long = "title")]
+        title: Option<String>,
+        /// Drop or approximate what cannot be converted instead of failing
+        #[structopt(long = "lenient")]
+        lenient: bool,
+        /// Write the conversion warnings to this file as JSON, each naming the collection it is about
+        #[structopt(long = "warnings-report")]
+        warnings_report: Option<String>,
+    },
+    /// Update a collection generated from an earlier version of the spec, keeping its scripts and variables
+    Sync {
+        /// Collection to update
+        collection: String,
+        /// OpenAPI document it is generated from
+        spec: String,
+        /// Output file to write, `-` for stdout
+        #[structopt(short = "o", long = "output", default_value = "-")]
+        output: String,
+        /// Config (JSON, YAML or TOML) holding the postman convert options the collection was generated with
+        #[structopt(short = "c", long = "config")]
+        config_file: Option<String>,
+        #[structopt(flatten)]
+        overrides: PostmanOverrides,
+        /// Drop or approximate what cannot be converted instead of failing
+        #[structopt(long = "lenient")]
+        lenient: bool,
+        /// Write the conversion warnings to this file as JSON
+        #[structopt(long = "warnings-report")]
+        warnings_report: Option<String>,
+    },
+    /// Print the JSON Schema of the config taken by `to-postman`, `to-openapi` or `merge-specs`
+    ConfigSchema {
+        #[structopt(possible_values = &["to-postman", "to-openapi", "merge-specs"])]
+        direction: String,
+    },
+}
+
+fn main() {
+    if let Err(err) = run(Cli::from_args()) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn read_config<T: serde::de::DeserializeOwned + Default>(config_file: Option<impl AsRef<Path>>) -> Result<T> {
+    match config_file {
+        Some(config_file) => read_option(config_file),
+        None => Ok(T::default()),
+    }
+}
+
+fn write_collection(path: &Path, collection: PostmanCollection2c1, postman_version: &str) -> Result<()> {
+    if postman_version == "2.0" {
+        write_json(path, &to_postman_collection_2c0(collection), false)
+    } else {
+        write_json(path, &collection, false)
+    }
+}
+
+/// File names for the collection names, anything but letters, digits, `-`, `_` and `.` becoming `_`. Names that
+/// end up the same, ignoring case as some filesystems do, are told apart by a `_2`, `_3`, ... suffix
+fn file_names<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut used: Vec<String> = Vec::new();
+    let mut file_names: Vec<String> = Vec::new();
+    for name in names {
+        let base: String = name.trim().chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' }).collect();
+        let mut file_name = base.clone();
+        let mut suffix = 1;
+        while used.contains(&file_name.to_lowercase()) {
+            suffix += 1;
+            file_name = format!("{}_{}", base, suffix);
+        }
+        used.push(file_name.to_lowercase());
+        file_names.push(file_name);
+    }
+    file_names
+}
+
+fn write_postman(openapi: OpenApi3, mut option: PostmanConvertOption, args: &ConvertArgs, postman_version: &str, environment: &Option<String>, warnings: &mut Vec<Warning>) -> Result<()> {
+    option.lenient |= args.lenient;
+    let title = openapi.info.title.clone();
+    let collections = match option.split {
+        Some(split) => {
+            if args.output == STDIO {
+                return Err(Error::Invalid {
+                    pointer: String::new(),
+                    message: String::from("--split writes a file per collection, give a directory with -o"),
+                });
+            }
+            let (collections, convert_warnings) = split_to_postman_collections(openapi, split, option)?;
+            warnings.extend(convert_warnings);
+            let dir = Path::new(&args.output);
+            std::fs::create_dir_all(dir).map_err(|err| Error::io(&args.output, err))?;
+            let names = file_names(collections.iter().map(|collection| collection.info.name.as_str()));
+            for (collection, name) in collections.iter().zip(names) {
+                write_collection(&dir.join(format!("{}.postman_collection.json", name)), collection.clone(), postman_version)?;
+            }
+            collections
+        },
+        None => {
+            let (postman_collection, convert_warnings) = to_postman_colletion_2c1(openapi, option)?;
+            warnings.extend(convert_warnings);
+            write_collection(Path::new(&args.output), postman_collection.clone(), postman_version)?;
+            vec![postman_collection]
+        },
+    };
+    if let Some(environment) = environment {
+        write_json(environment, &postman_environment(&title, &collections), true)?;
     }
     Ok(())
 }
+
+fn write_openapi(pman: PostmanCollection2c1, mut option: OpenApi3ConvertOption, args: &ConvertArgs, warnings: &mut Vec<Warning>) -> Result<()> {
+    option.lenient |= args.lenient;
+    let (swagger_json, convert_warnings) = to_openapi3(pman, option)?;
+    warnings.extend(convert_warnings);
+    write_json(&args.output, &swagger_json, true)
+}
+
+/// Prints the warnings and writes the report asked for
+fn report(warnings: &[Warning], warnings_report: &Option<String>) -> Result<()> {
+    for warning in warnings {
+        eprintln!("warning: {}", warning);
+    }
+    if let Some(warnings_report) = warnings_report {
+        write_json(warnings_report, &warnings, true)?;
+    }
+    Ok(())
+}
+
+/// Reads either kind of input as an OpenAPI document, converting collections with the default options
+fn read_as_openapi3(file: &str) -> Result<OpenApi3> {
+    match read_document(file, false)?.0 {
+        Document::OpenApi3(openapi) => Ok(openapi),
+        Document::Postman(pman) => Ok(to_openapi3(pman, OpenApi3ConvertOption::default())?.0),
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let mut warnings: Vec<Warning> = Vec::new();
+    match cli {
+        Cli::ToPostman { args, config_file, overrides, postman_version } => {
+            let option = overrides.apply(read_config(config_file.as_ref())?);
+            let openapi = if option.lenient || args.lenient {
+                let (openapi, parse_warnings) = read_openapi3_lenient(&args.file)?;
+                warnings.extend(parse_warnings);
+                openapi
+            } else {
+                read_openapi3(&args.file)?
+            };
+            let environment = overrides.environment.clone();
+            write_postman(openapi, option, &args, &postman_version, &environment, &mut warnings)?;
+            report(&warnings, &args.warnings_report)
+        },
+        Cli::ToOpenapi { args, config_file, overrides } => {
+            let option = overrides.apply(read_config(config_file.as_ref())?);
+            let pman = if option.lenient || args.lenient {
+                let (pman, parse_warnings) = read_postman_collection_file_lenient(&args.file)?;
+                warnings.extend(parse_warnings);
+                pman
+            } else {
+                read_postman_collection_file(&args.file)?
+            };
+            write_openapi(pman, option, &args, &mut warnings)?;
+            report(&warnings, &args.warnings_report)
+        },
+        Cli::Convert { args, to_postman_config, to_openapi_config, postman_overrides, openapi_overrides, postman_version } => {
+            let (document, parse_warnings) = read_document(&args.file, args.lenient)?;
+            warnings.extend(parse_warnings);
+            match document {
+                Document::OpenApi3(openapi) => write_postman(openapi, postman_overrides.apply(read_config(to_postman_config.as_ref())?), &args, &postman_version, &postman_overrides.environment, &mut warnings)?,
+                Document::Postman(pman) => write_openapi(pman, openapi_overrides.apply(read_config(to_openapi_config.as_ref())?), &args, &mut warnings)?,
+            }
+            report(&warnings, &args.warnings_report)
+        },
+        Cli::Validate { file, lenient } => {
+            let (document, parse_warnings) = read_document(&file, lenient)?;
+            warnings.extend(parse_warnings);
+            let kind = match document {
+                Document::OpenApi3(openapi) => {
+                    warnings.extend(to_postman_colletion_2c1(openapi, PostmanConvertOption::default().with_lenient(lenient))?.1);
+                    "OpenAPI document"
+                },
+                Document::Postman(pman) => {
+                    warnings.extend(to_openapi3(pman, OpenApi3ConvertOption::default().with_lenient(lenient))?.1);
+                    "postman collection"
+                },
+            };
+            report(&warnings, &None)?;
+            println!("{}: valid {}, {} warning(s)", file, kind, warnings.len());
+            Ok(())
+        },
+        Cli::Diff { old, new, format } => {
+            let changes = diff_operations(&read_as_openapi3(&old)?, &read_as_openapi3(&new)?);
+            match format.as_str() {
+                "json" => write_json(STDIO, &changes, true)?,
+                "markdown" => print!("{}", changes_markdown(&changes)),
+                _ => print!("{}", changes_text(&changes)),
+            }
+            // 1 is taken by errors, so CI tells a broken input from a broken API
+            if changes.iter().any(|change| change.breaking) {
+                std::process::exit(2);
+            }
+            Ok(())
+        },
+        Cli::Batch { input, output, to_postman_config, to_openapi_config, postman_overrides, openapi_overrides, postman_version, lenient } => {
+            let output_dir = PathBuf::from(output);
+            let (base, inputs) = find_inputs(&input, &output_dir)?;
+            let outcomes = run_batch(&inputs, &base, &output_dir, lenient, |file, document| {
+                let converted = match document {
+                    Document::OpenApi3(openapi) => {
+                        let config = sibling_config(file).or_else(|| to_postman_config.as_ref().map(PathBuf::from));
+                        let mut option = postman_overrides.apply(read_config(config)?);
+                        option.lenient |= lenient;
+                        let (collection, warnings) = to_postman_colletion_2c1(openapi, option)?;
+                        if postman_version == "2.0" {
+                            (serde_json::to_value(to_postman_collection_2c0(collection)), warnings)
+                        } else {
+                            (serde_json::to_value(collection), warnings)
+                        }
+                    },
+                    Document::Postman(pman) => {
+                        let config = sibling_config(file).or_else(|| to_openapi_config.as_ref().map(PathBuf::from));
+                        let mut option = openapi_overrides.apply(read_config(config)?);
+                        option.lenient |= lenient;
+                        let (openapi, warnings) = to_openapi3(pman, option)?;
+                        (serde_json::to_value(openapi), warnings)
+                    },
+                };
+                let (value, warnings) = converted;
+                let value = value.map_err(|err| Error::Invalid {
+                    pointer: String::new(),
+                    message: err.to_string(),
+                })?;
+                Ok((value, warnings))
+            });
+            for outcome in &outcomes {
+                for warning in &outcome.warnings {
+                    eprintln!("warning: {}: {}", outcome.input.display(), warning);
+                }
+            }
+            print!("{}", summary_table(&outcomes));
+            if outcomes.iter().any(|outcome| matches!(outcome.status, BatchStatus::Failed(_))) {
+                std::process::exit(1);
+            }
+            Ok(())
+        },
+        Cli::MergeSpecs { args } => {
+            let (collection, merge_warnings) = read_postman_merge(Path::new(&args.file), args.lenient)?;
+            warnings.extend(merge_warnings);
+            write_json(&args.output, &collection, false)?;
+            report(&warnings, &args.warnings_report)
+        },
+        Cli::MergeCollections { files, output, config_file, overrides, title, lenient, warnings_report } => {
+            let mut option: OpenApi3ConvertOption = overrides.apply(read_config(config_file.as_ref())?);
+            option.lenient |= lenient;
+            let mut pmans: Vec<(String, PostmanCollection2c1)> = Vec::new();
+            for file in &files {
+                if option.lenient {
+                    let (pman, parse_warnings) = read_postman_collection_file_lenient(file)?;
+                    warnings.extend(parse_warnings.into_iter().map(|warning| warning.with_source(Some(file))));
+                    pmans.push((file.clone(), pman));
+                } else {
+                    pmans.push((file.clone(), read_postman_collection_file(file)?));
+                }
+            }
+            let (mut openapi, merge_warnings) = merge_to_openapi3(pmans, option)?;
+            warnings.extend(merge_warnings);
+            if let Some(title) = title {
+                openapi.info.title = title;
+            }
+            write_json(&output, &openapi, true)?;
+            report(&warnings, &warnings_report)
+        },
+        Cli::Sync { collection, spec, output, config_file, overrides, lenient, warnings_report } => {
+            let mut option = overrides.apply(read_config(config_file.as_ref())?);
+            option.lenient |= lenient;
+            let (pman, openapi) = if option.lenient {
+                let (pman, parse_warnings) = read_postman_collection_file_lenient(&collection)?;
+                warnings.extend(parse_warnings);
+                let (openapi, parse_warnings) = read_openapi3_lenient(&spec)?;
+                warnings.extend(parse_warnings);
+                (pman, openapi)
+            } else {
+                (read_postman_collection_file(&collection)?, read_openapi3(&spec)?)
+            };
+            let (synced, summary, sync_warnings) = sync_postman_collection(pman, openapi, option)?;
+            warnings.extend(sync_warnings);
+            write_json(&output, &synced, false)?;
+            eprintln!("{} updated, {} added, {} removed", summary.updated.len(), summary.added.len(), summary.removed.len());
+            for name in &summary.added {
+                eprintln!("+ {}", name);
+            }
+            for name in &summary.removed {
+                eprintln!("- {}", name);
+            }
+            report(&warnings, &warnings_report)
+        },
+        Cli::ConfigSchema { direction } => {
+            let schema = match direction.as_str() {
+                "to-postman" => config_schema::<PostmanConvertOption>(),
+                "to-openapi" => config_schema::<OpenApi3ConvertOption>(),
+                _ => config_schema::<PostmanMergeOption>(),
+            };
+            write_json(STDIO, &schema, true)
+        },
+    }
+}
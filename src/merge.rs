@@ -0,0 +1,226 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use schemars::JsonSchema;
+use serde_json::Value;
+use crate::config::read_config;
+use crate::error::{Result, Warning};
+use crate::model::OpenApi3;
+use crate::postman_model::{to_postman_colletion_2c1, PostmanCollection2c1, PostmanCollectionAuth, PostmanCollectionInfo, PostmanCollectionItem, PostmanCollectionRequest, PostmanCollectionRequestAuth, PostmanCollectionRequestAuthItem, PostmanCollectionVariable, PostmanConvertOption, POSTMAN_SCHEMA_2C1};
+use crate::{read_openapi3, read_openapi3_lenient};
+
+/// One OpenAPI document of a merged collection
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PostmanMergeService {
+    /// Folder name, the document title when left out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// OpenAPI document, relative to the merge config
+    pub spec: String,
+    /// `to-postman` config of the document, relative to the merge config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preceding_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prepend_tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PostmanMergeOption {
+    /// Name of the merged collection
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Values of the collection variables, the other variables used by the requests are declared empty
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    pub services: Vec<PostmanMergeService>,
+}
+
+/// Names the service as the source of its warnings, the pointers being relative to its own document
+fn sourced(name: &str, warnings: Vec<Warning>) -> impl Iterator<Item = Warning> + '_ {
+    warnings.into_iter().map(move |warning| warning.with_source(Some(name)))
+}
+
+fn requests_mut(items: &mut [PostmanCollectionItem]) -> Vec<&mut PostmanCollectionRequest> {
+    let mut requests: Vec<&mut PostmanCollectionRequest> = Vec::new();
+    for PostmanCollectionItem { item, request, .. } in items.iter_mut() {
+        if let Some(children) = item {
+            requests.extend(requests_mut(children));
+        }
+        if let Some(request) = request {
+            requests.push(request);
+        }
+    }
+    requests
+}
+
+/// Token of the `Authorization: Bearer` header of a request
+fn bearer_token(request: &PostmanCollectionRequest) -> Option<String> {
+    request.header.iter()
+        .find(|header| header.key.eq_ignore_ascii_case("authorization"))
+        .and_then(|header| header.value.strip_prefix("Bearer "))
+        .map(str::to_owned)
+}
+
+fn bearer_auth(token: &str) -> PostmanCollectionAuth {
+    PostmanCollectionAuth::Known(PostmanCollectionRequestAuth {
+        _type: "bearer".to_owned(),
+        bearer: Some(vec![PostmanCollectionRequestAuthItem {
+            key: "token".to_owned(),
+            value: token.to_owned(),
+            _type: "string".to_owned(),
+            extensions: BTreeMap::new(),
+        }]),
+        basic: None,
+        extensions: BTreeMap::new(),
+    })
+}
+
+/// Takes the bearer header off the requests of a folder when they all send the same one, the requests
+/// without auth are marked `noauth` so they do not inherit it. Returns the token the folder should carry.
+fn hoist_bearer(folder: &mut PostmanCollectionItem) -> Option<String> {
+    let mut requests = requests_mut(folder.item.as_deref_mut().unwrap_or_default());
+    let tokens: BTreeSet<String> = requests.iter().filter_map(|request| bearer_token(request)).collect();
+    if tokens.len() != 1 {
+        return None;
+    }
+    for request in requests.iter_mut() {
+        if bearer_token(request).is_some() {
+            request.header.retain(|header| !header.key.eq_ignore_ascii_case("authorization"));
+        } else if request.auth.is_none() {
+            request.auth = Some(PostmanCollectionAuth::Known(PostmanCollectionRequestAuth {
+                _type: "noauth".to_owned(),
+                bearer: None,
+                basic: None,
+                extensions: BTreeMap::new(),
+            }));
+        }
+    }
+    tokens.into_iter().next()
+}
+
+/// Names of the `{{variables}}` used anywhere in `value`, leaving out postman's dynamic `{{$guid}}` ones
+pub(crate) fn collect_variables(value: &Value, variables: &mut BTreeSet<String>) {
+    match value {
+        Value::String(text) => {
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                rest = &rest[start + 2..];
+                match rest.find("}}") {
+                    Some(end) => {
+                        let name = rest[..end].trim();
+                        if !name.is_empty() && !name.starts_with('$') && !name.contains('{') {
+                            variables.insert(name.to_owned());
+                        }
+                        rest = &rest[end + 2..];
+                    },
+                    None => break,
+                }
+            }
+        },
+        Value::Array(list) => list.iter().for_each(|child| collect_variables(child, variables)),
+        Value::Object(obj) => obj.values().for_each(|child| collect_variables(child, variables)),
+        _ => {},
+    }
+}
+
+/// Converts each document with its own options into a folder of one collection. Bearer auth shared by
+/// the requests moves to the folder, or to the collection when every folder shares it, and the variables
+/// used by the requests are declared once on the collection.
+pub fn merge_to_postman(services: Vec<(String, OpenApi3, PostmanConvertOption)>, option: &PostmanMergeOption) -> Result<(PostmanCollection2c1, Vec<Warning>)> {
+    let mut warnings: Vec<Warning> = Vec::new();
+    let mut folders: Vec<PostmanCollectionItem> = Vec::new();
+    for (name, openapi, convert_option) in services {
+        let (collection, service_warnings) = to_postman_colletion_2c1(openapi, convert_option)?;
+        warnings.extend(sourced(&name, service_warnings));
+        folders.push(PostmanCollectionItem {
+            name,
+            item: Some(collection.item),
+            request: None,
+            response: None,
+            event: collection.event,
+            auth: None,
+            extensions: collection.extensions,
+        });
+    }
+    let tokens: Vec<Option<String>> = folders.iter_mut().map(hoist_bearer).collect();
+    let shared_token = match tokens.first() {
+        Some(Some(token)) if tokens.iter().all(|other| other.as_ref() == Some(token)) => Some(token.clone()),
+        _ => None,
+    };
+    let mut auth: Option<PostmanCollectionAuth> = None;
+    match &shared_token {
+        Some(token) => auth = Some(bearer_auth(token)),
+        None => {
+            for (folder, token) in folders.iter_mut().zip(tokens) {
+                folder.auth = token.map(|token| bearer_auth(&token));
+            }
+        },
+    }
+    let mut names: BTreeSet<String> = option.variables.keys().cloned().collect();
+    collect_variables(&serde_json::to_value(&folders).unwrap_or(Value::Null), &mut names);
+    collect_variables(&serde_json::to_value(&auth).unwrap_or(Value::Null), &mut names);
+    let variables: Vec<PostmanCollectionVariable> = names.into_iter().map(|name| PostmanCollectionVariable {
+        value: option.variables.get(&name).cloned().unwrap_or_default(),
+        key: name,
+        description: None,
+        extensions: BTreeMap::new(),
+    }).collect();
+    Ok((PostmanCollection2c1 {
+        info: PostmanCollectionInfo {
+            name: option.name.clone(),
+            description: option.description.clone().map(Into::into),
+            schema: POSTMAN_SCHEMA_2C1.into(),
+            extensions: BTreeMap::new(),
+        },
+        item: folders,
+        event: None,
+        auth,
+        variable: if !variables.is_empty() {
+            Some(variables)
+        } else {
+            None
+        },
+        extensions: BTreeMap::new(),
+    }, warnings))
+}
+
+/// Reads a merge config and the documents it lists, then merges them
+pub fn read_postman_merge(path: &Path, lenient: bool) -> Result<(PostmanCollection2c1, Vec<Warning>)> {
+    let option: PostmanMergeOption = read_config(path)?;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut warnings: Vec<Warning> = Vec::new();
+    let mut services: Vec<(String, OpenApi3, PostmanConvertOption)> = Vec::new();
+    for service in &option.services {
+        let (openapi, parse_warnings) = if lenient {
+            read_openapi3_lenient(dir.join(&service.spec))?
+        } else {
+            (read_openapi3(dir.join(&service.spec))?, Vec::new())
+        };
+        let mut convert_option: PostmanConvertOption = match &service.config {
+            Some(config) => read_config(&dir.join(config))?,
+            None => PostmanConvertOption::default(),
+        };
+        if let Some(host) = &service.host {
+            convert_option.host = host.clone();
+        }
+        if let Some(preceding_path) = &service.preceding_path {
+            convert_option.preceding_path = preceding_path.clone();
+        }
+        if let Some(prepend_tag) = &service.prepend_tag {
+            convert_option.prepend_tag = prepend_tag.clone();
+        }
+        convert_option.lenient |= lenient;
+        let name = service.name.clone().unwrap_or_else(|| openapi.info.title.clone());
+        warnings.extend(sourced(&name, parse_warnings));
+        services.push((name, openapi, convert_option));
+    }
+    let (collection, merge_warnings) = merge_to_postman(services, &option)?;
+    warnings.extend(merge_warnings);
+    Ok((collection, warnings))
+}
@@ -1,30 +1,133 @@
 use std::collections::BTreeMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::{Error, Unexpected};
 use serde_json::Value;
-use crate::postman_model::{PostmanCollection2c1, PostmanCollectionItem};
+use crate::error::{tolerate, Result, Warning, WarningKind};
+use crate::parameter_style::{infer_schema, parse_path, typed_example};
+use crate::postman_model::{PostmanCollection2c1, PostmanCollectionAuth, PostmanCollectionItem, PostmanCollectionEvent, PostmanCollectionRequest, PostmanCollectionResponse};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3 {
     pub info: OpenApi3Info,
     pub openapi: String,
-    pub paths: BTreeMap<String, OpenApi3Operations>,
+    pub paths: BTreeMap<String, OpenApi3PathItem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<OpenApi3Components>,
     pub servers: Vec<OpenApi3Server>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<OpenApi3Tag>>,
+    #[serde(rename = "x-postman-events")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postman_events: Option<Vec<PostmanCollectionEvent>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApi3Tag {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "x-postman-events")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postman_events: Option<Vec<PostmanCollectionEvent>>,
+    /// Postman folders nested in the tag folder that carry scripts
+    #[serde(rename = "x-postman-folders")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postman_folders: Option<Vec<OpenApi3PostmanFolder>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
+/// A postman folder below a tag folder, `path` naming the folders from the tag folder down
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApi3PostmanFolder {
+    pub path: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<Vec<PostmanCollectionEvent>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OpenApi3Server {
-    description: String,
-    url: String,
+    pub description: String,
+    pub url: String,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct OpenApi3ConvertOption {
     pub servers: Vec<OpenApi3Server>,
-    pub mapping: Vec<OpenApi3ConvertMap>
+    /// Postman values (usually environment variables) replaced by `value`
+    pub mapping: Vec<OpenApi3ConvertMap>,
+    /// Headers besides `X-API-Key` and `Api-Key` that become apiKey security schemes
+    #[serde(default)]
+    pub api_key_headers: Vec<String>,
+    /// Headers dropped from the operations, on top of the transport headers
+    #[serde(default)]
+    pub ignore_headers: Vec<String>,
+    /// Keep `Content-Type`, `Accept`, `Authorization` and api key headers as header parameters
+    #[serde(default)]
+    pub keep_transport_headers: bool,
+    /// Turn unsupported constructs into warnings instead of errors
+    #[serde(default)]
+    pub lenient: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl OpenApi3ConvertOption {
+    pub fn with_server(mut self, url: &str, description: &str) -> Self {
+        self.servers.push(OpenApi3Server {
+            description: description.to_owned(),
+            url: url.to_owned(),
+            extensions: BTreeMap::new(),
+        });
+        self
+    }
+    /// Replaces the postman value `key` (usually an environment variable) by `value`
+    pub fn with_mapping(mut self, key: &str, value: &str) -> Self {
+        self.mapping.push(OpenApi3ConvertMap {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        });
+        self
+    }
+    pub fn with_api_key_header(mut self, name: &str) -> Self {
+        self.api_key_headers.push(name.to_owned());
+        self
+    }
+    pub fn with_ignored_header(mut self, name: &str) -> Self {
+        self.ignore_headers.push(name.to_owned());
+        self
+    }
+    pub fn with_transport_headers_kept(mut self, keep: bool) -> Self {
+        self.keep_transport_headers = keep;
+        self
+    }
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+    pub fn is_api_key_header(&self, name: &str) -> bool {
+        let lc_name = name.to_lowercase();
+        lc_name == "x-api-key" || lc_name == "api-key" || self.api_key_headers.iter().any(|h| h.to_lowercase() == lc_name)
+    }
+    /// Whether a postman header is left out of the operation parameters, the OpenAPI spec ignores header
+    /// parameters named `Content-Type`, `Accept` and `Authorization`
+    pub fn is_ignored_header(&self, name: &str) -> bool {
+        let lc_name = name.to_lowercase();
+        if self.ignore_headers.iter().any(|h| h.to_lowercase() == lc_name) {
+            return true;
+        }
+        !self.keep_transport_headers
+            && (lc_name == "content-type" || lc_name == "accept" || lc_name == "authorization" || self.is_api_key_header(name))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct OpenApi3ConvertMap {
     pub key: String,
     pub value: String,
@@ -32,18 +135,32 @@ pub struct OpenApi3ConvertMap {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3Components {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<BTreeMap<String, OpenApi3Parameter>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schemas: Option<BTreeMap<String, OpenApi3Schema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<BTreeMap<String, OpenApi3Example>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "securitySchemes")]
     pub security_schemes: Option<BTreeMap<String, OpenApi3ComponentsSecuritySchemesItem>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3ComponentsSecuritySchemesItem {
     #[serde(rename = "in")]
-    pub _in: String,
-    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _in: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     #[serde(rename = "type")]
     pub _type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +168,8 @@ pub struct OpenApi3Info {
     pub description: String,
     pub title: String,
     pub version: String,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,27 +179,161 @@ pub struct OpenApi3Parameter {
     pub _in: String,
     pub name: String,
     pub required: bool,
-    pub schema: OpenApi3Schema,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<OpenApi3Schema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<OpenApi3ResponseContents>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    #[serde(rename = "allowReserved")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_reserved: Option<bool>,
+    #[serde(rename = "allowEmptyValue")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_empty_value: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<OpenApi3Examples>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl OpenApi3Parameter {
+    pub fn new(name: &str, _in: &str, schema: OpenApi3Schema) -> OpenApi3Parameter {
+        OpenApi3Parameter {
+            description: String::from(""),
+            _in: _in.to_owned(),
+            name: name.to_owned(),
+            required: false,
+            schema: Some(schema),
+            content: None,
+            style: None,
+            explode: None,
+            allow_reserved: None,
+            allow_empty_value: None,
+            deprecated: None,
+            example: None,
+            examples: None,
+            extensions: BTreeMap::new(),
+        }
+    }
+    /// The schema of the parameter, taken from the first media type when `content` is used instead
+    pub fn get_schema(&self) -> Option<&OpenApi3Schema> {
+        self.schema.as_ref().or_else(|| {
+            self.content.as_ref()
+                .and_then(|content| content.values().next())
+                .and_then(|media| media.schema.as_ref())
+        })
+    }
+    pub fn example_value(&self) -> Option<Value> {
+        self.example.clone()
+            .or_else(|| self.examples.as_ref().and_then(first_example_value))
+            .or_else(|| self.get_schema().and_then(|schema| schema.example_value()))
+    }
+    /// `style` with the default of the parameter location applied
+    pub fn get_style(&self) -> &str {
+        match &self.style {
+            Some(style) => style,
+            None if self._in == "query" || self._in == "cookie" => "form",
+            None => "simple",
+        }
+    }
+    /// `explode` with the default of the style applied
+    pub fn get_explode(&self) -> bool {
+        self.explode.unwrap_or_else(|| self.get_style() == "form")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApi3Reference {
+    #[serde(rename = "$ref")]
+    pub _ref: String,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum OpenApi3ParameterOrRef {
+    Parameter(OpenApi3Parameter),
+    Ref(OpenApi3Reference),
+}
+
+impl OpenApi3ParameterOrRef {
+    /// Follows a `#/components/parameters/...` reference, `None` when it cannot be found
+    pub fn resolve<'a>(&'a self, components: &'a Option<OpenApi3Components>) -> Option<&'a OpenApi3Parameter> {
+        match self {
+            OpenApi3ParameterOrRef::Parameter(parameter) => Some(parameter),
+            OpenApi3ParameterOrRef::Ref(reference) => {
+                let name = reference._ref.trim_start_matches("#/components/parameters/");
+                components.as_ref()
+                    .and_then(|c| c.parameters.as_ref())
+                    .and_then(|parameters| parameters.get(name))
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3SchemaArrayType {
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
     pub items: Box<OpenApi3Schema>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<Value>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3SchemaObjectType {
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
     pub properties: BTreeMap<String, Box<OpenApi3Schema>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<Value>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+/// The `type` of a scalar schema, failing on any other type so that the untagged `OpenApi3Schema` falls through
+/// to the variant of the type
+fn scalar_type<'de, D: Deserializer<'de>>(deserializer: D, expected: &str) -> std::result::Result<String, D::Error> {
+    let _type = String::deserialize(deserializer)?;
+    if _type == expected {
+        Ok(_type)
+    } else {
+        Err(D::Error::invalid_value(Unexpected::Str(&_type), &expected))
+    }
+}
+fn string_type<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    scalar_type(deserializer, "string")
+}
+fn integer_type<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    scalar_type(deserializer, "integer")
+}
+fn number_type<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    scalar_type(deserializer, "number")
+}
+fn boolean_type<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    scalar_type(deserializer, "boolean")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3SchemaStringType {
     #[serde(rename = "type")]
+    #[serde(deserialize_with = "string_type")]
     pub _type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
     #[serde(rename = "enum")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub _enum: Option<Vec<String>>,
@@ -88,42 +341,119 @@ pub struct OpenApi3SchemaStringType {
     pub default: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<Value>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3SchemaIntegerType {
     #[serde(rename = "type")]
+    #[serde(deserialize_with = "integer_type")]
     pub _type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<Value>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApi3SchemaNumberType {
+    #[serde(rename = "type")]
+    #[serde(deserialize_with = "number_type")]
+    pub _type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3SchemaBooleanType {
     #[serde(rename = "type")]
+    #[serde(deserialize_with = "boolean_type")]
     pub _type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<Value>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3SchemaRefType {
     #[serde(rename = "$ref")]
-    pub _ref: Option<String>,
+    pub _ref: String,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
+#[allow(clippy::enum_variant_names)]
 pub enum OpenApi3Schema {
     ArrayType(OpenApi3SchemaArrayType),
     ObjectType(OpenApi3SchemaObjectType),
     StringType(OpenApi3SchemaStringType),
     IntegerType(OpenApi3SchemaIntegerType),
+    NumberType(OpenApi3SchemaNumberType),
     BooleanType(OpenApi3SchemaBooleanType),
+    RefType(OpenApi3SchemaRefType),
+    /// Any other shape (`allOf`, `oneOf`, an object without `properties`, ...), kept as it is
+    Raw(Value),
+}
+
+impl OpenApi3Schema {
+    pub fn string(example: Option<Value>) -> OpenApi3Schema {
+        OpenApi3Schema::StringType(OpenApi3SchemaStringType {
+            _type: String::from("string"),
+            format: None,
+            _enum: None,
+            default: None,
+            example,
+            extensions: BTreeMap::new(),
+        })
+    }
+    /// The `enum` of a string schema, or of the items of an array schema
+    pub fn enum_values(&self) -> Option<Vec<String>> {
+        match self {
+            OpenApi3Schema::StringType(s) => s._enum.clone(),
+            OpenApi3Schema::ArrayType(s) => s.items.enum_values(),
+            _ => None,
+        }
+    }
+    /// The `example` of the schema, arrays and objects without one are built from their items and properties
+    pub fn example_value(&self) -> Option<Value> {
+        match self {
+            OpenApi3Schema::ArrayType(s) => s.example.clone().or_else(|| {
+                s.items.example_value().map(|item| Value::Array(vec![item]))
+            }),
+            OpenApi3Schema::ObjectType(s) => s.example.clone().or_else(|| {
+                let properties: serde_json::Map<String, Value> = s.properties.iter()
+                    .filter_map(|(name, property)| property.example_value().map(|v| (name.clone(), v)))
+                    .collect();
+                if !properties.is_empty() {
+                    Some(Value::Object(properties))
+                } else {
+                    None
+                }
+            }),
+            OpenApi3Schema::StringType(s) => s.example.clone(),
+            OpenApi3Schema::IntegerType(s) => s.example.clone(),
+            OpenApi3Schema::NumberType(s) => s.example.clone(),
+            OpenApi3Schema::BooleanType(s) => s.example.clone(),
+            OpenApi3Schema::RefType(_) => None,
+            OpenApi3Schema::Raw(schema) => schema.get("example").cloned(),
+        }
+    }
 }
 
 pub type OpenApi3OperationMethodSecurity = Option<Vec<BTreeMap<String, Vec<String>>>>;
@@ -134,32 +464,45 @@ pub struct OpenApi3OperationMethod {
     #[serde(rename = "operationId")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub operation_id: Option<String>,
-    pub parameters: Vec<OpenApi3Parameter>,
+    pub parameters: Vec<OpenApi3ParameterOrRef>,
     pub responses: OpenApi3Responses,
     pub tags: Vec<String>,
     #[serde(rename = "requestBody")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub request_body: Option<OpenApi3OperationMethodRequestBody>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub security: OpenApi3OperationMethodSecurity
+    pub security: OpenApi3OperationMethodSecurity,
+    #[serde(rename = "x-postman-events")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postman_events: Option<Vec<PostmanCollectionEvent>>,
+    /// Postman folders the request sat in below its tag folder
+    #[serde(rename = "x-postman-folder")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postman_folder: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3OperationMethodRequestBody {
     pub content: OpenApi3OperationMethodRequestBodyContent,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OpenApi3OperationMethodRequestBodyJson {
-    pub schema: OpenApi3Schema,
+pub struct OpenApi3MediaType {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<OpenApi3Schema>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<OpenApi3Examples>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum OpenApi3OperationMethodRequestBodyContent {
-    #[serde(rename = "application/json")]
-    ApplicationJson(OpenApi3OperationMethodRequestBodyJson)
-}
+
+pub type OpenApi3OperationMethodRequestBodyContent = BTreeMap<String, OpenApi3MediaType>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApi3Response {
@@ -167,6 +510,8 @@ pub struct OpenApi3Response {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<OpenApi3ResponseContents>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -175,130 +520,941 @@ pub struct OpenApi3ResponseContent {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schema: Option<OpenApi3Schema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<OpenApi3Examples>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApi3Example {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(rename = "externalValue")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_value: Option<String>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl OpenApi3Example {
+    pub fn new(summary: &str, value: Value) -> OpenApi3Example {
+        OpenApi3Example {
+            summary: Some(summary.to_owned()),
+            description: None,
+            value: Some(value),
+            external_value: None,
+            extensions: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OpenApi3ExampleOrRef {
+    Ref(OpenApi3Reference),
+    Example(OpenApi3Example),
+}
+
+impl OpenApi3ExampleOrRef {
+    /// Follows a `#/components/examples/...` reference, `None` when it cannot be found
+    pub fn resolve<'a>(&'a self, components: &'a Option<OpenApi3Components>) -> Option<&'a OpenApi3Example> {
+        match self {
+            OpenApi3ExampleOrRef::Example(example) => Some(example),
+            OpenApi3ExampleOrRef::Ref(reference) => {
+                let name = reference._ref.trim_start_matches("#/components/examples/");
+                components.as_ref()
+                    .and_then(|c| c.examples.as_ref())
+                    .and_then(|examples| examples.get(name))
+            },
+        }
+    }
+    /// The inline `value`, references are not followed
+    pub fn value(&self) -> Option<&Value> {
+        match self {
+            OpenApi3ExampleOrRef::Example(example) => example.value.as_ref(),
+            OpenApi3ExampleOrRef::Ref(_) => None,
+        }
+    }
+}
+
+pub type OpenApi3Examples = BTreeMap<String, OpenApi3ExampleOrRef>;
+
+fn first_example_value(examples: &OpenApi3Examples) -> Option<Value> {
+    examples.values().find_map(|example| example.value().cloned())
 }
 
 pub type OpenApi3ResponseContents = BTreeMap<String, OpenApi3ResponseContent>;
 
-pub type OpenApi3Operations = BTreeMap<String, OpenApi3OperationMethod>;
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApi3PathItem {
+    #[serde(rename = "$ref")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub servers: Option<Vec<OpenApi3Server>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<OpenApi3ParameterOrRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get: Option<OpenApi3OperationMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub put: Option<OpenApi3OperationMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post: Option<OpenApi3OperationMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<OpenApi3OperationMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<OpenApi3OperationMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<OpenApi3OperationMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patch: Option<OpenApi3OperationMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<OpenApi3OperationMethod>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl OpenApi3PathItem {
+    pub fn operations(&self) -> Vec<(&'static str, &OpenApi3OperationMethod)> {
+        let methods = [
+            ("get", &self.get),
+            ("put", &self.put),
+            ("post", &self.post),
+            ("delete", &self.delete),
+            ("options", &self.options),
+            ("head", &self.head),
+            ("patch", &self.patch),
+            ("trace", &self.trace),
+        ];
+        methods.iter()
+            .filter_map(|(method, operation)| operation.as_ref().map(|o| (*method, o)))
+            .collect()
+    }
+    /// `None` when the method is not one of the http methods a path item supports
+    pub fn operation_mut(&mut self, method: &str) -> Option<&mut Option<OpenApi3OperationMethod>> {
+        match method {
+            "get" => Some(&mut self.get),
+            "put" => Some(&mut self.put),
+            "post" => Some(&mut self.post),
+            "delete" => Some(&mut self.delete),
+            "options" => Some(&mut self.options),
+            "head" => Some(&mut self.head),
+            "patch" => Some(&mut self.patch),
+            "trace" => Some(&mut self.trace),
+            _ => None,
+        }
+    }
+    /// The path item without its operations
+    pub fn without_operations(&self) -> OpenApi3PathItem {
+        OpenApi3PathItem {
+            _ref: self._ref.clone(),
+            summary: self.summary.clone(),
+            description: self.description.clone(),
+            servers: self.servers.clone(),
+            parameters: self.parameters.clone(),
+            extensions: self.extensions.clone(),
+            ..OpenApi3PathItem::default()
+        }
+    }
+    /// Path level parameters followed by the operation ones, an operation parameter replaces the
+    /// path level parameter with the same name and location
+    pub fn merged_parameters<'a>(&'a self, operation: &'a OpenApi3OperationMethod, components: &'a Option<OpenApi3Components>) -> Vec<&'a OpenApi3Parameter> {
+        let mut merged: Vec<&OpenApi3Parameter> = self.parameters.iter()
+            .filter_map(|p| p.resolve(components))
+            .collect();
+        for parameter in operation.parameters.iter().filter_map(|p| p.resolve(components)) {
+            match merged.iter().position(|m| m.name == parameter.name && m._in == parameter._in) {
+                Some(index) => merged[index] = parameter,
+                None => merged.push(parameter),
+            }
+        }
+        merged
+    }
+}
 
 pub type OpenApi3Responses = BTreeMap<String, OpenApi3Response>;
 
-fn pmanitem_to_oa3ops(paths: &mut BTreeMap<String, OpenApi3Operations>, pmanitem: &PostmanCollectionItem, tag: &Option<String>, convert_option: &OpenApi3ConvertOption) {
-    let response_empty_vec = Vec::new();
-    let pman_responses = if pmanitem.response.is_some() {
-        pmanitem.response.as_ref().unwrap()
-    } else {
-        &response_empty_vec
-    };
-    if let Some(pmanrequest) = &pmanitem.request {
-        let mut tags = Vec::new();
-        if let Some(tagstr) = tag {
-            tags.push(tagstr.to_owned());
-        }
-        let mut parameters = pmanrequest.oa3_get_parameters_for_header_query(&convert_option);
-        let (mut path, variables) = pmanrequest.url.path_extract();
-        for variable in variables {
-            parameters.push(OpenApi3Parameter {
-                description: String::from(""),
-                _in: String::from("path"),
-                name: variable.key.clone(),
-                required: true,
-                schema: OpenApi3Schema::StringType(OpenApi3SchemaStringType {
-                    _type: String::from("string"),
-                    _enum: None,
-                    default: None,
-                    example: Some(Value::String(variable.value.clone()))
-                })
-            })
-        }
-        for mapp in &convert_option.mapping {
-            path = str::replace(path.clone().as_ref(), &mapp.key, &mapp.value);
-        }
-        let method = pmanrequest.method.to_ascii_lowercase();
-        let inner = paths.get_mut(&path);
-        let mut responses: OpenApi3Responses = BTreeMap::new();
-        for resp in pman_responses {
-            if resp.code.is_some() {
-                let mut response_content: OpenApi3ResponseContents = BTreeMap::new();
-                response_content.insert(String::from("application/json"), OpenApi3ResponseContent {
-                    description: None,
-                    // schema: Some(OpenApi3Schema::RefType(OpenApi3SchemaRefType {
-                    //     _ref: Some(String::from("#/components/schemas/Any"))
-                    // })),
-                    schema: Some(OpenApi3Schema::ObjectType(OpenApi3SchemaObjectType {
-                        properties: BTreeMap::new(),
-                        example: Some(Value::String(resp.body.clone()))
-                    })),
-                });
-                responses.insert(format!("{}", resp.code.as_ref().unwrap()), OpenApi3Response {
-                    description: Some(resp.status.as_ref().unwrap().clone()),
-                    content: Some(response_content),
-                });
+/// Collection field holding the original document (without paths) in round trip mode
+pub const ROUND_TRIP_DOCUMENT_KEY: &str = "x-openapi";
+/// Request item field holding the original operation in round trip mode
+pub const ROUND_TRIP_OPERATION_KEY: &str = "x-openapi-operation";
+/// Request item field holding the operationId, which `sync` matches requests by
+pub const OPERATION_ID_KEY: &str = "x-openapi-operation-id";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApi3RoundTripOperation {
+    pub path: String,
+    pub method: String,
+    #[serde(rename = "pathItem")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_item: Option<OpenApi3PathItem>,
+    pub operation: OpenApi3OperationMethod,
+    /// The request as exported, telling what was edited in postman since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<PostmanCollectionRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<Vec<PostmanCollectionResponse>>,
+}
+
+/// OpenAPI `x-` extensions are carried as same-named fields on the matching postman object
+/// (document to collection, info to info, tag to folder, operation to request item)
+pub fn x_extensions(extensions: &BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+    extensions.iter()
+        .filter(|(key, _)| key.starts_with("x-") && ![ROUND_TRIP_DOCUMENT_KEY, ROUND_TRIP_OPERATION_KEY, OPERATION_ID_KEY].contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// An operation converted from a single postman request, before the requests for the same path and method are combined
+struct PmanOperation {
+    name: String,
+    /// Index of the input collection holding the request
+    source: usize,
+    /// JSON pointer of the request item in the collection
+    location: String,
+    path: String,
+    method: String,
+    operation: OpenApi3OperationMethod,
+    /// Filled in from the exported operation, its parameter schemas are not inferred again
+    restored: bool,
+}
+
+/// Requests converted so far and the warnings raised on the way
+struct PmanConversion {
+    /// Index of the input collection being converted
+    source: usize,
+    operations: Vec<PmanOperation>,
+    /// Nested folders with scripts, by the tag of their top level folder
+    folders: Vec<(String, OpenApi3PostmanFolder)>,
+    /// Components of the exported document, resolving the `$ref`s of exported operations
+    components: Option<OpenApi3Components>,
+    /// Path and tags of the exported operations, by the method and path their request converts to
+    restored_paths: BTreeMap<(String, String), (String, Vec<String>)>,
+    warnings: Vec<Warning>,
+}
+
+/// Converts one postman request, `location` being its JSON pointer, into the path, method and operation it calls.
+/// Scripts, folders and extensions of the item are left to the caller
+fn pmanrequest_to_oa3op(pmanrequest: &PostmanCollectionRequest, pman_responses: &[PostmanCollectionResponse], tag: &Option<String>, location: &str, convert_option: &OpenApi3ConvertOption, warnings: &mut Vec<Warning>) -> Result<(String, String, OpenApi3OperationMethod)> {
+    let mut tags = Vec::new();
+    if let Some(tagstr) = tag {
+        tags.push(tagstr.to_owned());
+    }
+    let mut parameters: Vec<OpenApi3ParameterOrRef> = pmanrequest.oa3_get_parameters_for_header_query(convert_option)
+        .into_iter()
+        .map(OpenApi3ParameterOrRef::Parameter)
+        .collect();
+    let (mut path, variables) = pmanrequest.url.path_extract();
+    for variable in variables {
+        let parameter = parse_path(&variable.key, &variable.value);
+        parameters.push(OpenApi3ParameterOrRef::Parameter(OpenApi3Parameter {
+            description: variable.description.as_ref().map(|d| d.text().to_owned()).unwrap_or_default(),
+            ..parameter
+        }));
+    }
+    for mapp in &convert_option.mapping {
+        path = str::replace(&path, &mapp.key, &mapp.value);
+    }
+    let response_media_type = pmanrequest.media_type("accept");
+    let mut responses: OpenApi3Responses = BTreeMap::new();
+    // saved responses sharing a status code become named examples of that response
+    let mut first_names: BTreeMap<String, String> = BTreeMap::new();
+    for resp in pman_responses {
+        if let Some(code) = &resp.code {
+            let name = resp.name.clone().or_else(|| resp.status.clone()).unwrap_or_else(|| format!("{}", code));
+            let existing = responses.get_mut(&format!("{}", code))
+                .and_then(|response| response.content.as_mut())
+                .and_then(|contents| contents.get_mut(&response_media_type));
+            if let Some(content) = existing {
+                let examples = content.examples.get_or_insert_with(BTreeMap::new);
+                if examples.is_empty() {
+                    if let Some(value) = content.schema.as_ref().and_then(|schema| schema.example_value()) {
+                        insert_example(examples, &first_names[&format!("{}", code)], value);
+                    }
+                }
+                insert_example(examples, &name, Value::String(resp.body.clone()));
+                continue;
             }
-        }
-        if responses.len() == 0 {
+            first_names.insert(format!("{}", code), name);
             let mut response_content: OpenApi3ResponseContents = BTreeMap::new();
-            response_content.insert(String::from("application/json"), OpenApi3ResponseContent {
+            response_content.insert(response_media_type.clone(), OpenApi3ResponseContent {
                 description: None,
+                example: None,
+                examples: None,
                 // schema: Some(OpenApi3Schema::RefType(OpenApi3SchemaRefType {
                 //     _ref: Some(String::from("#/components/schemas/Any"))
                 // })),
                 schema: Some(OpenApi3Schema::ObjectType(OpenApi3SchemaObjectType {
+                    _type: Some(String::from("object")),
                     properties: BTreeMap::new(),
-                    example: None
+                    example: Some(Value::String(resp.body.clone())),
+                    extensions: BTreeMap::new(),
                 })),
+                extensions: BTreeMap::new(),
             });
-            responses.insert(String::from("200"), OpenApi3Response {
-                description: Some(String::from("OK")),
+            responses.insert(format!("{}", code), OpenApi3Response {
+                description: Some(resp.status.as_ref().unwrap().clone()),
                 content: Some(response_content),
+                extensions: BTreeMap::new(),
             });
         }
-        let oa3opmethod = OpenApi3OperationMethod {
-            description: pmanrequest.description.clone(),
-            operation_id: None,
-            parameters: parameters,
-            responses: responses,
-            tags: tags,
-            security: pmanrequest.oa3_get_securities(),
-            request_body: pmanrequest.oa3_get_request_body(),
-        };
-        if let Some(innerbmap) = inner {
-            innerbmap.insert(method, oa3opmethod);
+    }
+    if responses.is_empty() {
+        let mut response_content: OpenApi3ResponseContents = BTreeMap::new();
+        response_content.insert(response_media_type.clone(), OpenApi3ResponseContent {
+            description: None,
+            example: None,
+            examples: None,
+            // schema: Some(OpenApi3Schema::RefType(OpenApi3SchemaRefType {
+            //     _ref: Some(String::from("#/components/schemas/Any"))
+            // })),
+            schema: Some(OpenApi3Schema::ObjectType(OpenApi3SchemaObjectType {
+                _type: Some(String::from("object")),
+                properties: BTreeMap::new(),
+                example: None,
+                extensions: BTreeMap::new(),
+            })),
+            extensions: BTreeMap::new(),
+        });
+        responses.insert(String::from("200"), OpenApi3Response {
+            description: Some(String::from("OK")),
+            content: Some(response_content),
+            extensions: BTreeMap::new(),
+        });
+    }
+    let operation = OpenApi3OperationMethod {
+        description: pmanrequest.description.as_ref().map(|d| d.text().to_owned()),
+        operation_id: None,
+        parameters,
+        responses,
+        tags,
+        security: pmanrequest.oa3_get_securities(convert_option),
+        request_body: match pmanrequest.oa3_get_request_body(&format!("{}/request", location)) {
+            Ok(request_body) => request_body,
+            Err(err) => {
+                tolerate(err, WarningKind::Dropped, convert_option.lenient, warnings)?;
+                None
+            },
+        },
+        postman_events: None,
+        postman_folder: None,
+        extensions: BTreeMap::new(),
+    };
+    let auth_message = match &pmanrequest.auth {
+        Some(PostmanCollectionAuth::Known(auth)) if auth._type == "bearer" || auth._type == "noauth" => None,
+        Some(PostmanCollectionAuth::Known(auth)) => Some(format!("`{}` auth has no security scheme counterpart", auth._type)),
+        Some(auth) => Some(format!("`{}` auth of a shape the model cannot read", auth.auth_type())),
+        None => None,
+    };
+    if let Some(message) = auth_message {
+        warnings.push(Warning::new(WarningKind::Dropped, &format!("{}/request/auth", location), message));
+    }
+    Ok((path, pmanrequest.method.to_ascii_lowercase(), operation))
+}
+/// The example name of a request variant `to-postman` named `path (example)`, other names as they are
+fn example_name(name: &str, path: &str) -> String {
+    name.strip_prefix(path)
+        .and_then(|rest| rest.strip_prefix(" ("))
+        .and_then(|rest| rest.strip_suffix(')'))
+        .unwrap_or(name)
+        .to_owned()
+}
+/// `location` is the JSON pointer of `pmanitem` in the collection and `folders` names the folders holding it,
+/// from the top level down
+fn pmanitem_to_oa3ops(paths: &mut BTreeMap<String, OpenApi3PathItem>, conversion: &mut PmanConversion, pmanitem: &PostmanCollectionItem, tag: &Option<String>, folders: &[String], location: &str, convert_option: &OpenApi3ConvertOption) -> Result<()> {
+    if let Some(pmanrequest) = &pmanitem.request {
+        let pman_responses = pmanitem.response.as_deref().unwrap_or_default();
+        let (mut path, method, mut operation) = pmanrequest_to_oa3op(pmanrequest, pman_responses, tag, location, convert_option, &mut conversion.warnings)?;
+        operation.postman_events = pmanitem.event.clone().filter(|events| !events.is_empty());
+        operation.postman_folder = if folders.len() > 1 {
+            Some(folders[1..].to_vec())
         } else {
-            let mut bmap = BTreeMap::new();
-            bmap.insert(method, oa3opmethod);
-            paths.insert(path, bmap);
+            None
+        };
+        operation.extensions = x_extensions(&pmanitem.extensions);
+        operation.operation_id = pmanitem.extensions.get(OPERATION_ID_KEY).and_then(Value::as_str).map(str::to_owned);
+        let round_trip = pmanitem.extensions.get(ROUND_TRIP_OPERATION_KEY).and_then(|value| {
+            match serde_json::from_value::<OpenApi3RoundTripOperation>(value.clone()) {
+                Ok(round_trip) => Some(round_trip),
+                Err(err) => {
+                    let message = format!("cannot read the exported operation, the request is converted as is: {}", err);
+                    conversion.warnings.push(Warning::new(WarningKind::Dropped, &format!("{}/{}", location, ROUND_TRIP_OPERATION_KEY), message));
+                    None
+                },
+            }
+        });
+        let restored = round_trip.is_some();
+        if let Some(round_trip) = round_trip {
+            let converted_path = path.clone();
+            path = oa3_restore_operation(paths, &mut operation, path, tag, round_trip, &conversion.components, convert_option);
+            conversion.restored_paths.insert((method.clone(), converted_path), (path.clone(), operation.tags.clone()));
         }
+        conversion.operations.push(PmanOperation {
+            name: example_name(&pmanitem.name, &pmanrequest.url.path_extract().0),
+            source: conversion.source,
+            location: location.to_owned(),
+            path,
+            method,
+            operation,
+            restored,
+        });
     }
     if let Some(inneritem) = &pmanitem.item {
-        for pmaniteminner in inneritem.as_ref() {
-            pmanitem_to_oa3ops(paths, pmaniteminner, tag, &convert_option)
+        let mut inner_folders = folders.to_vec();
+        inner_folders.push(pmanitem.name.clone());
+        // top level folder events stay on their tag, nested ones on the tag too, under the folder path
+        if let Some(top) = folders.first() {
+            let extensions = x_extensions(&pmanitem.extensions);
+            if pmanitem.event.is_some() || !extensions.is_empty() {
+                conversion.folders.push((top.clone(), OpenApi3PostmanFolder {
+                    path: inner_folders[1..].to_vec(),
+                    event: pmanitem.event.clone(),
+                    extensions,
+                }));
+            }
+        }
+        for (index, pmaniteminner) in inneritem.iter().enumerate() {
+            pmanitem_to_oa3ops(paths, conversion, pmaniteminner, tag, &inner_folders, &format!("{}/item/{}", location, index), convert_option)?;
         }
     }
+    Ok(())
 }
-pub fn to_openapi3(pman: PostmanCollection2c1, convert_option: OpenApi3ConvertOption) -> OpenApi3 {
-    let mut paths: BTreeMap<String, OpenApi3Operations> = BTreeMap::new();
 
-    for pmanitem in pman.item {
-        pmanitem_to_oa3ops(&mut paths, &pmanitem, &Some(pmanitem.name.clone()), &convert_option)
+/// Whether `value` is `example`, a raw postman body being the same example as the JSON value it was written from
+fn same_example(example: &Value, value: &Value) -> bool {
+    match (example, value) {
+        (Value::String(_), _) => example == value,
+        (_, Value::String(raw)) => serde_json::from_str::<Value>(raw).is_ok_and(|parsed| parsed == *example),
+        _ => example == value,
+    }
+}
+/// Adds `value` under a name not used yet in `examples`
+fn insert_example(examples: &mut OpenApi3Examples, name: &str, value: Value) {
+    if examples.values().any(|example| example.value().is_some_and(|example| same_example(example, &value))) {
+        return;
+    }
+    let mut key = name.to_owned();
+    let mut count = 1;
+    while examples.contains_key(&key) {
+        count += 1;
+        key = format!("{} {}", name, count);
+    }
+    examples.insert(key, OpenApi3ExampleOrRef::Example(OpenApi3Example::new(name, value)));
+}
+/// Folds a later request for the same path and method into `operation`
+fn oa3_merge_operation(operation: &mut OpenApi3OperationMethod, other: OpenApi3OperationMethod, name: &str) {
+    if operation.description.is_none() {
+        operation.description = other.description;
+    }
+    for parameter_or_ref in other.parameters {
+        let exists = match parameter_or_ref.resolve(&None) {
+            Some(parameter) => operation.parameters.iter()
+                .filter_map(|p| p.resolve(&None))
+                .any(|p| p._in == parameter._in && p.name == parameter.name),
+            None => true,
+        };
+        if !exists {
+            operation.parameters.push(parameter_or_ref);
+        }
+    }
+    for tag in other.tags {
+        if !operation.tags.contains(&tag) {
+            operation.tags.push(tag);
+        }
     }
+    if let Some(other_security) = other.security {
+        let security = operation.security.get_or_insert_with(Vec::new);
+        for requirement in other_security {
+            if !security.contains(&requirement) {
+                security.push(requirement);
+            }
+        }
+    }
+    if let Some(other_body) = other.request_body {
+        match &mut operation.request_body {
+            Some(request_body) => {
+                for (media_type, other_media) in other_body.content {
+                    match request_body.content.get_mut(&media_type) {
+                        Some(media) => {
+                            let examples = media.examples.get_or_insert_with(BTreeMap::new);
+                            if let Some(value) = other_media.example {
+                                insert_example(examples, name, value);
+                            }
+                        },
+                        None => {
+                            request_body.content.insert(media_type, other_media);
+                        },
+                    }
+                }
+            },
+            None => operation.request_body = Some(other_body),
+        }
+    }
+    for (code, other_response) in other.responses {
+        match operation.responses.get_mut(&code) {
+            Some(response) => {
+                let contents = response.content.get_or_insert_with(BTreeMap::new);
+                for (media_type, other_content) in other_response.content.unwrap_or_default() {
+                    match contents.get_mut(&media_type) {
+                        Some(content) => {
+                            let value = other_content.schema.as_ref().and_then(|schema| schema.example_value())
+                                .filter(|value| !content.example.as_ref().is_some_and(|example| same_example(example, value)));
+                            if let Some(value) = value {
+                                insert_example(content.examples.get_or_insert_with(BTreeMap::new), name, value);
+                            }
+                        },
+                        None => {
+                            contents.insert(media_type, other_content);
+                        },
+                    }
+                }
+            },
+            None => {
+                operation.responses.insert(code, other_response);
+            },
+        }
+    }
+    if let Some(other_events) = other.postman_events {
+        let events = operation.postman_events.get_or_insert_with(Vec::new);
+        for event in other_events {
+            let value = serde_json::to_value(&event).ok();
+            if !events.iter().any(|e| serde_json::to_value(e).ok() == value) {
+                events.push(event);
+            }
+        }
+    }
+    for (key, value) in other.extensions {
+        operation.extensions.entry(key).or_insert(value);
+    }
+}
+/// Moves the single `example` of a merged body or response into `examples`, named after the first request
+fn oa3_name_first_examples(operation: &mut OpenApi3OperationMethod, name: &str) {
+    if let Some(request_body) = &mut operation.request_body {
+        for media in request_body.content.values_mut() {
+            if media.examples.as_ref().is_some_and(BTreeMap::is_empty) {
+                media.examples = None;
+            }
+            if let Some(examples) = &mut media.examples {
+                if let Some(value) = media.example.take() {
+                    let mut named = BTreeMap::new();
+                    insert_example(&mut named, name, value);
+                    for (key, example) in std::mem::take(examples) {
+                        named.entry(key).or_insert(example);
+                    }
+                    *examples = named;
+                }
+            }
+        }
+    }
+    for response in operation.responses.values_mut() {
+        for content in response.content.iter_mut().flat_map(|contents| contents.values_mut()) {
+            if content.examples.as_ref().is_some_and(BTreeMap::is_empty) {
+                content.examples = None;
+            }
+            if let Some(examples) = &mut content.examples {
+                if let Some(value) = content.schema.as_ref().and_then(|schema| schema.example_value()) {
+                    insert_example(examples, name, value);
+                }
+            }
+        }
+    }
+}
+/// Combines the converted requests into the paths, requests sharing a path and method are merged into one
+/// operation and reported in the returned warnings. Every request hitting a path is used to infer the parameter
+/// schemas, and a query parameter is required only when it is required in every request for the operation.
+/// `sources` names the input collections, `None` when a single one is converted
+fn oa3_combine_operations(paths: &mut BTreeMap<String, OpenApi3PathItem>, converted: Vec<PmanOperation>, sources: &[Option<String>]) -> Vec<Warning> {
+    let mut warnings: Vec<Warning> = Vec::new();
+    let mut samples: BTreeMap<(String, String, String), Vec<Value>> = BTreeMap::new();
+    for pmanop in &converted {
+        for parameter in pmanop.operation.parameters.iter().filter_map(|p| p.resolve(&None)) {
+            if let Some(example) = parameter.example_value() {
+                samples.entry((pmanop.path.clone(), parameter._in.clone(), parameter.name.clone())).or_default().push(example);
+            }
+        }
+    }
+    let mut grouped: Vec<((String, String), Vec<PmanOperation>)> = Vec::new();
+    for pmanop in converted {
+        let key = (pmanop.path.clone(), pmanop.method.clone());
+        match grouped.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, operations)) => operations.push(pmanop),
+            None => grouped.push((key, vec![pmanop])),
+        }
+    }
+    for ((path, method), operations) in grouped {
+        let names: Vec<String> = operations.iter().map(|o| o.name.clone()).collect();
+        let mut inputs: Vec<usize> = Vec::new();
+        for pmanop in &operations {
+            if !inputs.contains(&pmanop.source) {
+                inputs.push(pmanop.source);
+            }
+        }
+        let first_location = operations[0].location.clone();
+        let first_source = sources[operations[0].source].as_deref();
+        let restored = operations.iter().any(|o| o.restored);
+        let required_queries: Vec<String> = operations.iter()
+            .flat_map(|o| o.operation.parameters.iter())
+            .filter_map(|p| p.resolve(&None))
+            .filter(|p| p._in == "query")
+            .filter(|p| operations.iter().all(|o| {
+                o.operation.parameters.iter()
+                    .filter_map(|q| q.resolve(&None))
+                    .any(|q| q._in == "query" && q.name == p.name && q.required)
+            }))
+            .map(|p| p.name.clone())
+            .collect();
+        let mut iter = operations.into_iter();
+        let mut operation = iter.next().unwrap().operation;
+        for other in iter {
+            oa3_merge_operation(&mut operation, other.operation, &other.name);
+        }
+        if names.len() > 1 {
+            oa3_name_first_examples(&mut operation, &names[0]);
+            if inputs.len() > 1 {
+                let defined_by: Vec<&str> = inputs.iter().filter_map(|&input| sources[input].as_deref()).collect();
+                let message = format!("{} {} is defined by several collections, merged: {}", method.to_uppercase(), path, defined_by.join(", "));
+                warnings.push(Warning::new(WarningKind::Conflict, &first_location, message).with_source(first_source));
+            } else if !restored {
+                let message = format!("merged {} requests into {} {}: {}", names.len(), method.to_uppercase(), path, names.join(", "));
+                warnings.push(Warning::new(WarningKind::Merged, &first_location, message).with_source(first_source));
+            }
+        }
+        for parameter_or_ref in operation.parameters.iter_mut().filter(|_| !restored) {
+            if let OpenApi3ParameterOrRef::Parameter(parameter) = parameter_or_ref {
+                let key = (path.clone(), parameter._in.clone(), parameter.name.clone());
+                if let (Some(schema), Some(values)) = (&parameter.schema, samples.get(&key)) {
+                    parameter.schema = Some(infer_schema(schema, values));
+                }
+                if parameter._in == "query" {
+                    parameter.required = required_queries.contains(&parameter.name);
+                }
+            }
+        }
+        match paths.entry(path.clone()).or_default().operation_mut(&method) {
+            Some(slot) => *slot = Some(operation),
+            None => {
+                let message = format!("{} {} dropped, an OpenAPI path item has no `{}` operation", method.to_uppercase(), path, method);
+                warnings.push(Warning::new(WarningKind::Dropped, &format!("{}/request/method", first_location), message).with_source(first_source));
+            },
+        }
+    }
+    warnings
+}
+/// Whether `a` and `b` serialize to the same JSON
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+/// The parameter of `parameters` with the same location and name as `parameter`
+fn find_parameter<'a>(parameters: &'a [OpenApi3ParameterOrRef], parameter: &OpenApi3Parameter) -> Option<&'a OpenApi3Parameter> {
+    parameters.iter()
+        .filter_map(|p| p.resolve(&None))
+        .find(|p| p._in == parameter._in && (p.name == parameter.name || (p._in == "header" && p.name.eq_ignore_ascii_case(&parameter.name))))
+}
+/// Fills what a postman request cannot hold (operationId, schemas, `$ref`s, responses, ...) into `operation`, converted
+/// from the live request, from the operation it was exported from. What converts as it did from the exported
+/// request comes back from `round_trip` unchanged, what was edited in postman since is kept. Returns the path
+fn oa3_restore_operation(paths: &mut BTreeMap<String, OpenApi3PathItem>, operation: &mut OpenApi3OperationMethod, path: String, tag: &Option<String>, round_trip: OpenApi3RoundTripOperation, components: &Option<OpenApi3Components>, convert_option: &OpenApi3ConvertOption) -> String {
+    let exported = match &round_trip.request {
+        Some(request) => {
+            let responses = round_trip.response.as_deref().unwrap_or_default();
+            match pmanrequest_to_oa3op(request, responses, tag, "", convert_option, &mut Vec::new()) {
+                Ok(exported) => exported,
+                Err(_) => return path,
+            }
+        },
+        // exported before the request was kept along, taken as not edited
+        None => (path.clone(), String::new(), operation.clone()),
+    };
+    let (exported_path, _, before) = exported;
+    let OpenApi3RoundTripOperation { path: kept_path, path_item: kept_path_item, operation: kept, .. } = round_trip;
+    // the path comes back when the url was not edited, along with the path item it was exported from
+    let (path, path_level) = if exported_path == path {
+        let path_item = paths.entry(kept_path.clone()).or_insert_with(|| kept_path_item.unwrap_or_default());
+        (kept_path, path_item.parameters.clone())
+    } else {
+        (path, Vec::new())
+    };
+
+    let mut parameters: Vec<OpenApi3ParameterOrRef> = Vec::new();
+    let candidates = path_level.iter().map(|p| (true, p)).chain(kept.parameters.iter().map(|p| (false, p)));
+    for (on_path, parameter_or_ref) in candidates {
+        let parameter = match parameter_or_ref.resolve(components) {
+            Some(parameter) => parameter,
+            None => {
+                if !on_path {
+                    parameters.push(parameter_or_ref.clone());
+                }
+                continue;
+            },
+        };
+        match (find_parameter(&before.parameters, parameter), find_parameter(&operation.parameters, parameter)) {
+            // removed in postman
+            (Some(_), None) if !on_path => {},
+            (Some(exported), Some(edited)) if !same(exported, edited) => {
+                let description = if exported.description != edited.description {
+                    edited.description.clone()
+                } else {
+                    parameter.description.clone()
+                };
+                let example = edited.example_value().map(|value| match parameter.get_schema() {
+                    Some(OpenApi3Schema::IntegerType(schema)) => typed_example(&value, &schema._type),
+                    Some(OpenApi3Schema::NumberType(schema)) => typed_example(&value, &schema._type),
+                    Some(OpenApi3Schema::StringType(schema)) => typed_example(&value, &schema._type),
+                    Some(OpenApi3Schema::BooleanType(_)) => typed_example(&value, "boolean"),
+                    _ => value,
+                });
+                parameters.push(OpenApi3ParameterOrRef::Parameter(OpenApi3Parameter {
+                    description,
+                    example,
+                    ..parameter.clone()
+                }));
+            },
+            _ if !on_path => parameters.push(parameter_or_ref.clone()),
+            _ => {},
+        }
+    }
+    // headers the export wrote from the security schemes are in the exported request too
+    for added in operation.parameters.iter().filter_map(|p| p.resolve(&None)) {
+        let known = path_level.iter().chain(kept.parameters.iter())
+            .filter_map(|p| p.resolve(components))
+            .any(|p| p._in == added._in && (p.name == added.name || (p._in == "header" && p.name.eq_ignore_ascii_case(&added.name))));
+        if !known && find_parameter(&before.parameters, added).is_none() {
+            parameters.push(OpenApi3ParameterOrRef::Parameter(added.clone()));
+        }
+    }
+    operation.parameters = parameters;
+
+    if same(&before.description, &operation.description) {
+        operation.description = kept.description;
+    }
+    if same(&before.security, &operation.security) {
+        operation.security = kept.security;
+    }
+    operation.request_body = if same(&before.request_body, &operation.request_body) {
+        kept.request_body
+    } else {
+        match (operation.request_body.take(), kept.request_body) {
+            (Some(edited), Some(mut request_body)) if edited.content.keys().eq(request_body.content.keys()) => {
+                for (media_type, media) in edited.content {
+                    if let Some(kept_media) = request_body.content.get_mut(&media_type) {
+                        kept_media.example = match (media.example, &kept_media.example) {
+                            (Some(Value::String(raw)), Some(example)) if !example.is_string() => Some(serde_json::from_str(&raw).unwrap_or(Value::String(raw))),
+                            (Some(Value::String(raw)), None) => Some(serde_json::from_str(&raw).unwrap_or(Value::String(raw))),
+                            (example, _) => example,
+                        };
+                    }
+                }
+                Some(request_body)
+            },
+            (edited, _) => edited,
+        }
+    };
+    let mut responses = kept.responses;
+    if !same(&before.responses, &operation.responses) {
+        for (code, response) in std::mem::take(&mut operation.responses) {
+            responses.entry(code).or_insert(response);
+        }
+    }
+    operation.responses = responses;
+    // the folder postman keeps the request in names the first tag, after the `prependTag` of the export
+    let first_tag = kept.tags.first().map(String::as_str).unwrap_or("_base");
+    if tag.as_ref().is_some_and(|tag| tag.ends_with(first_tag)) {
+        operation.tags = kept.tags;
+    }
+    operation.operation_id = kept.operation_id;
+    let mut extensions: BTreeMap<String, Value> = kept.extensions.into_iter()
+        .filter(|(key, _)| !key.starts_with("x-"))
+        .collect();
+    extensions.append(&mut operation.extensions);
+    operation.extensions = extensions;
+    path
+}
+/// Gives the requests without an auth of their own the auth of the nearest folder, or of the collection, having one
+fn inherit_auth(items: &mut [PostmanCollectionItem], inherited: &Option<PostmanCollectionAuth>) {
+    for item in items.iter_mut() {
+        let auth = item.auth.clone().or_else(|| inherited.clone());
+        if let Some(request) = &mut item.request {
+            if request.auth.is_none() {
+                request.auth = auth.clone();
+            }
+        }
+        if let Some(children) = &mut item.item {
+            inherit_auth(children, &auth);
+        }
+    }
+}
+/// Converts a collection, also returning the warnings raised while converting
+pub fn to_openapi3(pman: PostmanCollection2c1, convert_option: OpenApi3ConvertOption) -> Result<(OpenApi3, Vec<Warning>)> {
+    collections_to_openapi3(vec![(None, pman)], convert_option)
+}
+/// Converts several collections into one document, each given with the name of its input, usually its file.
+/// The same path and method in different collections are merged like requests of one collection and reported
+/// as conflicts, folders of the same name become one tag. Warnings carry the input their pointer is in.
+pub fn merge_to_openapi3(pmans: Vec<(String, PostmanCollection2c1)>, convert_option: OpenApi3ConvertOption) -> Result<(OpenApi3, Vec<Warning>)> {
+    collections_to_openapi3(pmans.into_iter().map(|(source, pman)| (Some(source), pman)).collect(), convert_option)
+}
+fn collections_to_openapi3(pmans: Vec<(Option<String>, PostmanCollection2c1)>, convert_option: OpenApi3ConvertOption) -> Result<(OpenApi3, Vec<Warning>)> {
+    let mut paths: BTreeMap<String, OpenApi3PathItem> = BTreeMap::new();
+
+    let mut conversion = PmanConversion {
+        source: 0,
+        operations: Vec::new(),
+        folders: Vec::new(),
+        components: None,
+        restored_paths: BTreeMap::new(),
+        warnings: Vec::new(),
+    };
+    let mut tags: Vec<OpenApi3Tag> = Vec::new();
+    let mut postman_events: Vec<PostmanCollectionEvent> = Vec::new();
+    let mut round_trip_document: Option<OpenApi3> = None;
+    let sources: Vec<Option<String>> = pmans.iter().map(|(source, _)| source.clone()).collect();
+    let title = pmans.iter().map(|(_, pman)| pman.info.name.clone()).collect::<Vec<String>>().join(", ");
+    let mut info_extensions: BTreeMap<String, Value> = BTreeMap::new();
+    let mut extensions: BTreeMap<String, Value> = BTreeMap::new();
+    for (input, (source, mut pman)) in pmans.into_iter().enumerate() {
+        conversion.source = input;
+        let first_warning = conversion.warnings.len();
+        let document = pman.extensions.get(ROUND_TRIP_DOCUMENT_KEY).and_then(|value| {
+            match serde_json::from_value::<OpenApi3>(value.clone()) {
+                Ok(document) => Some(document),
+                Err(err) => {
+                    let message = format!("cannot read the exported document, the collection is converted as is: {}", err);
+                    conversion.warnings.push(Warning::new(WarningKind::Dropped, &format!("/{}", ROUND_TRIP_DOCUMENT_KEY), message));
+                    None
+                },
+            }
+        });
+        conversion.components = document.as_ref().and_then(|document| document.components.clone());
+        inherit_auth(&mut pman.item, &pman.auth);
+        for (index, pmanitem) in pman.item.into_iter().enumerate() {
+            pmanitem_to_oa3ops(&mut paths, &mut conversion, &pmanitem, &Some(pmanitem.name.clone()), &[], &format!("/item/{}", index), &convert_option)?;
+            if pmanitem.item.is_some() && !tags.iter().any(|tag| tag.name == pmanitem.name) {
+                tags.push(OpenApi3Tag {
+                    name: pmanitem.name.clone(),
+                    description: None,
+                    postman_events: pmanitem.event.clone(),
+                    postman_folders: None,
+                    extensions: x_extensions(&pmanitem.extensions),
+                });
+            }
+        }
+        for warning in &mut conversion.warnings[first_warning..] {
+            warning.source = source.clone();
+        }
+        if round_trip_document.is_none() {
+            round_trip_document = document;
+        }
+        postman_events.extend(pman.event.into_iter().flatten());
+        for (key, value) in x_extensions(&pman.info.extensions) {
+            info_extensions.entry(key).or_insert(value);
+        }
+        for (key, value) in x_extensions(&pman.extensions) {
+            extensions.entry(key).or_insert(value);
+        }
+    }
+    for (name, folder) in conversion.folders {
+        if let Some(tag) = tags.iter_mut().find(|tag| tag.name == name) {
+            let folders = tag.postman_folders.get_or_insert_with(Vec::new);
+            if !folders.iter().any(|kept| kept.path == folder.path) {
+                folders.push(folder);
+            }
+        }
+    }
+    // request variants join the exported operation of the request they were generated with
+    for pmanop in conversion.operations.iter_mut().filter(|pmanop| !pmanop.restored) {
+        if let Some((path, tags)) = conversion.restored_paths.get(&(pmanop.method.clone(), pmanop.path.clone())) {
+            pmanop.path = path.clone();
+            pmanop.operation.tags = tags.clone();
+        }
+    }
+    let mut warnings = conversion.warnings;
+    warnings.extend(oa3_combine_operations(&mut paths, conversion.operations, &sources));
     let mut security_schemes = BTreeMap::new();
     security_schemes.insert("bearer".to_owned(), OpenApi3ComponentsSecuritySchemesItem {
-        _in: String::from("header"),
-        name: String::from("Authorization"),
+        _in: Some(String::from("header")),
+        name: Some(String::from("Authorization")),
         _type: String::from("apiKey"),
+        scheme: None,
+        extensions: BTreeMap::new(),
     });
-    OpenApi3 {
+    for path_item in paths.values() {
+        for (_, operation) in path_item.operations() {
+            for requirement in operation.security.iter().flatten() {
+                for scheme_name in requirement.keys() {
+                    if convert_option.is_api_key_header(scheme_name) {
+                        security_schemes.entry(scheme_name.clone()).or_insert_with(|| OpenApi3ComponentsSecuritySchemesItem {
+                            _in: Some(String::from("header")),
+                            name: Some(scheme_name.clone()),
+                            _type: String::from("apiKey"),
+                            scheme: None,
+                            extensions: BTreeMap::new(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    if let Some(document) = round_trip_document {
+        return Ok((OpenApi3 {
+            paths,
+            ..document
+        }, warnings));
+    }
+    Ok((OpenApi3 {
         info: OpenApi3Info {
             description: String::from(""),
-            title: pman.info.name.clone(),
+            title,
             version: String::from("0.1.0"),
+            extensions: info_extensions,
         },
         openapi: String::from("3.0.0"),
-        paths: paths,
+        paths,
         components: Some(OpenApi3Components {
             security_schemes: Some(security_schemes),
+            schemas: None,
+            examples: None,
+            parameters: None,
+            extensions: BTreeMap::new(),
         }),
-        servers: convert_option.servers
+        servers: convert_option.servers,
+        tags: if !tags.is_empty() {
+            Some(tags)
+        } else {
+            None
+        },
+        postman_events: if !postman_events.is_empty() {
+            Some(postman_events)
+        } else {
+            None
+        },
+        extensions,
+    }, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn deserializes_scalar_schemas_by_type() {
+        let schema = |value: Value| serde_json::from_value::<OpenApi3Schema>(value).unwrap();
+        assert!(matches!(schema(json!({ "type": "string", "format": "uuid" })), OpenApi3Schema::StringType(_)));
+        assert!(matches!(schema(json!({ "type": "integer", "format": "int64" })), OpenApi3Schema::IntegerType(_)));
+        assert!(matches!(schema(json!({ "type": "number" })), OpenApi3Schema::NumberType(_)));
+        assert!(matches!(schema(json!({ "type": "boolean", "default": false })), OpenApi3Schema::BooleanType(_)));
+        assert!(matches!(schema(json!({ "type": "null" })), OpenApi3Schema::Raw(_)));
     }
 }
@@ -0,0 +1,511 @@
+use std::collections::BTreeMap;
+use serde_json::Value;
+use crate::model::{OpenApi3Parameter, OpenApi3Schema, OpenApi3SchemaArrayType, OpenApi3SchemaObjectType, OpenApi3SchemaStringType, OpenApi3SchemaIntegerType, OpenApi3SchemaNumberType, OpenApi3SchemaBooleanType};
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "".to_owned(),
+        _ => value.to_string(),
+    }
+}
+
+fn object_pairs(obj: &serde_json::Map<String, Value>) -> Vec<(String, String)> {
+    obj.iter().map(|(k, v)| (k.clone(), value_to_string(v))).collect()
+}
+
+/// `p1,v1,p2,v2` when not exploded, `p1=v1,p2=v2` when exploded
+fn join_object(obj: &serde_json::Map<String, Value>, explode: bool, separator: &str) -> String {
+    object_pairs(obj).iter()
+        .map(|(k, v)| if explode { format!("{}={}", k, v) } else { format!("{},{}", k, v) })
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+fn join_array(arr: &[Value], separator: &str) -> String {
+    arr.iter().map(value_to_string).collect::<Vec<String>>().join(separator)
+}
+
+/// Query entries for a `query` parameter following its `style` and `explode`
+pub fn serialize_query(parameter: &OpenApi3Parameter, value: &Value) -> Vec<(String, String)> {
+    let name = parameter.name.clone();
+    let explode = parameter.get_explode();
+    match (parameter.get_style(), value) {
+        ("deepObject", Value::Object(obj)) => object_pairs(obj).into_iter()
+            .map(|(k, v)| (format!("{}[{}]", name, k), v))
+            .collect(),
+        ("spaceDelimited", Value::Array(arr)) if !explode => vec![(name, join_array(arr, "%20"))],
+        ("pipeDelimited", Value::Array(arr)) if !explode => vec![(name, join_array(arr, "|"))],
+        ("spaceDelimited", Value::Object(obj)) if !explode => vec![(name, join_object(obj, false, "%20").replace(',', "%20"))],
+        ("pipeDelimited", Value::Object(obj)) if !explode => vec![(name, join_object(obj, false, "|").replace(',', "|"))],
+        (_, Value::Array(arr)) if explode => arr.iter().map(|v| (name.clone(), value_to_string(v))).collect(),
+        (_, Value::Array(arr)) => vec![(name, join_array(arr, ","))],
+        (_, Value::Object(obj)) if explode => object_pairs(obj),
+        (_, Value::Object(obj)) => vec![(name, join_object(obj, false, ","))],
+        (_, v) => vec![(name, value_to_string(v))],
+    }
+}
+
+/// The value of a `path` parameter, `label` and `matrix` styles include their prefix
+pub fn serialize_path(parameter: &OpenApi3Parameter, value: &Value) -> String {
+    let explode = parameter.get_explode();
+    let name = &parameter.name;
+    match (parameter.get_style(), value) {
+        ("label", Value::Array(arr)) => format!(".{}", join_array(arr, if explode { "." } else { "," })),
+        ("label", Value::Object(obj)) => format!(".{}", join_object(obj, explode, if explode { "." } else { "," })),
+        ("label", v) => format!(".{}", value_to_string(v)),
+        ("matrix", Value::Array(arr)) if explode => arr.iter().map(|v| format!(";{}={}", name, value_to_string(v))).collect(),
+        ("matrix", Value::Array(arr)) => format!(";{}={}", name, join_array(arr, ",")),
+        ("matrix", Value::Object(obj)) if explode => object_pairs(obj).iter().map(|(k, v)| format!(";{}={}", k, v)).collect(),
+        ("matrix", Value::Object(obj)) => format!(";{}={}", name, join_object(obj, false, ",")),
+        ("matrix", v) => format!(";{}={}", name, value_to_string(v)),
+        (_, v) => serialize_simple(v, explode),
+    }
+}
+
+/// `simple` style used by headers and by default for path parameters
+pub fn serialize_simple(value: &Value, explode: bool) -> String {
+    match value {
+        Value::Array(arr) => join_array(arr, ","),
+        Value::Object(obj) => join_object(obj, explode, ","),
+        v => value_to_string(v),
+    }
+}
+
+/// The `name=value` pair of a `cookie` parameter
+pub fn serialize_cookie(parameter: &OpenApi3Parameter, value: &Value) -> String {
+    format!("{}={}", parameter.name, serialize_simple(value, false))
+}
+
+/// Line added to postman query descriptions listing the enum choices of the parameter
+pub const ENUM_DESCRIPTION_PREFIX: &str = "Available values: ";
+
+pub struct QueryEntry {
+    pub key: String,
+    pub value: Option<Value>,
+    pub disabled: bool,
+    pub description: Option<String>,
+}
+
+/// Splits the enum choices written by `to-postman` off a postman query description, the last paragraph when
+/// it starts with `ENUM_DESCRIPTION_PREFIX`
+fn parse_description(description: &str) -> (String, Option<Vec<String>>) {
+    let (text, last) = match description.rfind("\n\n") {
+        Some(start) => (&description[..start], &description[(start + 2)..]),
+        None => ("", description),
+    };
+    match last.strip_prefix(ENUM_DESCRIPTION_PREFIX).filter(|choices| !choices.contains('\n')) {
+        Some(choices) => (text.to_owned(), Some(choices.split(", ").map(|c| c.to_owned()).collect())),
+        None => (description.to_owned(), None),
+    }
+}
+
+/// The parameter name of a query key and the property of a `name[property]` key
+fn split_query_key(key: &str) -> (String, Option<String>) {
+    match (key.find('['), key.ends_with(']')) {
+        (Some(start), true) if start > 0 && start + 2 < key.len() => (key[..start].to_owned(), Some(key[(start + 1)..(key.len() - 1)].to_owned())),
+        _ => (key.to_owned(), None),
+    }
+}
+
+fn string_schema(example: Option<Value>, choices: &Option<Vec<String>>) -> OpenApi3Schema {
+    match OpenApi3Schema::string(example) {
+        OpenApi3Schema::StringType(mut s) => {
+            s._enum = choices.clone();
+            OpenApi3Schema::StringType(s)
+        },
+        schema => schema,
+    }
+}
+
+/// Rebuild query parameters from postman query entries, repeated keys and `name[]` keys become exploded arrays
+/// and `name[property]` keys become `deepObject` objects. A parameter is required unless one of its entries is disabled.
+pub fn parse_query(queries: &[QueryEntry]) -> Vec<OpenApi3Parameter> {
+    let mut names: Vec<String> = Vec::new();
+    // (property of a `name[property]` key, entry) per parameter name
+    let mut grouped: BTreeMap<String, Vec<(Option<String>, &QueryEntry)>> = BTreeMap::new();
+    for entry in queries {
+        let (name, property) = split_query_key(&entry.key);
+        if !names.contains(&name) {
+            names.push(name.clone());
+        }
+        grouped.entry(name).or_default().push((property, entry));
+    }
+    let mut params: Vec<OpenApi3Parameter> = Vec::new();
+    for name in names {
+        let entries = &grouped[&name];
+        let (description, choices) = entries.iter()
+            .find_map(|(_, entry)| entry.description.as_ref().filter(|d| !d.is_empty()))
+            .map(|d| parse_description(d))
+            .unwrap_or_default();
+        let parameter = if entries.iter().all(|(property, _)| property.is_some()) {
+            let mut properties: BTreeMap<String, Box<OpenApi3Schema>> = BTreeMap::new();
+            let mut example = serde_json::Map::new();
+            for (property, entry) in entries {
+                let property = property.clone().unwrap_or_default();
+                properties.insert(property.clone(), Box::new(OpenApi3Schema::string(entry.value.clone())));
+                example.insert(property, entry.value.clone().unwrap_or(Value::Null));
+            }
+            OpenApi3Parameter {
+                style: Some("deepObject".to_owned()),
+                explode: Some(true),
+                ..OpenApi3Parameter::new(&name, "query", OpenApi3Schema::ObjectType(OpenApi3SchemaObjectType {
+                    _type: Some(String::from("object")),
+                    properties,
+                    example: Some(Value::Object(example)),
+                    extensions: BTreeMap::new(),
+                }))
+            }
+        } else if entries.len() > 1 || name.ends_with("[]") {
+            let values: Vec<Value> = entries.iter().map(|(_, entry)| entry.value.clone().unwrap_or(Value::Null)).collect();
+            OpenApi3Parameter::new(&name, "query", OpenApi3Schema::ArrayType(OpenApi3SchemaArrayType {
+                _type: Some(String::from("array")),
+                items: Box::new(string_schema(None, &choices)),
+                example: Some(Value::Array(values)),
+                extensions: BTreeMap::new(),
+            }))
+        } else {
+            OpenApi3Parameter::new(&name, "query", string_schema(entries[0].1.value.clone(), &choices))
+        };
+        params.push(OpenApi3Parameter {
+            description,
+            required: entries.iter().all(|(_, entry)| !entry.disabled),
+            ..parameter
+        });
+    }
+    params
+}
+
+/// A path parameter holding `values`, an array when there are several
+fn path_parameter(name: &str, style: &str, explode: bool, values: Vec<&str>) -> OpenApi3Parameter {
+    if values.len() > 1 {
+        OpenApi3Parameter {
+            style: Some(style.to_owned()),
+            explode: if explode { Some(true) } else { None },
+            ..OpenApi3Parameter::new(name, "path", OpenApi3Schema::ArrayType(OpenApi3SchemaArrayType {
+                _type: Some(String::from("array")),
+                items: Box::new(OpenApi3Schema::string(None)),
+                example: Some(Value::Array(values.iter().map(|v| Value::String((*v).to_owned())).collect())),
+                extensions: BTreeMap::new(),
+            }))
+        }
+    } else {
+        OpenApi3Parameter {
+            style: Some(style.to_owned()),
+            ..OpenApi3Parameter::new(name, "path", OpenApi3Schema::string(Some(Value::String(values[0].to_owned()))))
+        }
+    }
+}
+
+/// Rebuild a path parameter from a postman path variable, values written in `matrix` or `label` style are recognised
+pub fn parse_path(name: &str, value: &str) -> OpenApi3Parameter {
+    let matrix_prefix = format!(";{}=", name);
+    let parameter = if let Some(rest) = value.strip_prefix(&matrix_prefix) {
+        match rest.split(&matrix_prefix).collect::<Vec<&str>>() {
+            values if values.len() > 1 => path_parameter(name, "matrix", true, values),
+            _ => path_parameter(name, "matrix", false, rest.split(',').collect()),
+        }
+    } else if let Some(rest) = value.strip_prefix('.').filter(|rest| !rest.is_empty()) {
+        match rest.split('.').collect::<Vec<&str>>() {
+            values if values.len() > 1 => path_parameter(name, "label", true, values),
+            _ => path_parameter(name, "label", false, rest.split(',').collect()),
+        }
+    } else {
+        OpenApi3Parameter::new(name, "path", OpenApi3Schema::string(Some(Value::String(value.to_owned()))))
+    };
+    OpenApi3Parameter {
+        required: true,
+        ..parameter
+    }
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36 && value.char_indices().all(|(i, c)| match i {
+        8 | 13 | 18 | 23 => c == '-',
+        _ => c.is_ascii_hexdigit(),
+    })
+}
+
+/// `2019-10-12T07:20:50Z`, fractions of seconds and offsets are accepted after the seconds
+fn is_date_time(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() >= 20 && bytes[..19].iter().enumerate().all(|(i, c)| match i {
+        4 | 7 => *c == b'-',
+        10 => *c == b'T' || *c == b't',
+        13 | 16 => *c == b':',
+        _ => c.is_ascii_digit(),
+    })
+}
+
+/// `integer` or `number` when `value` is written as a JSON number: `-` as the only sign, no leading zeros,
+/// no `NaN` or `inf`
+fn json_number_type(value: &str) -> Option<&'static str> {
+    let digits = |s: &str| s.bytes().take_while(u8::is_ascii_digit).count();
+    let unsigned = value.strip_prefix('-').unwrap_or(value);
+    let integer = digits(unsigned);
+    if integer == 0 || (integer > 1 && unsigned.starts_with('0')) {
+        return None;
+    }
+    let mut rest = &unsigned[integer..];
+    let mut _type = "integer";
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let count = digits(fraction);
+        if count == 0 {
+            return None;
+        }
+        rest = &fraction[count..];
+        _type = "number";
+    }
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        let count = digits(exponent);
+        if count == 0 {
+            return None;
+        }
+        rest = &exponent[count..];
+        _type = "number";
+    }
+    if rest.is_empty() {
+        Some(_type)
+    } else {
+        None
+    }
+}
+
+/// The type and format a single postman value looks like, `None` for empty values and `{{variables}}`
+fn infer_type(value: &Value) -> Option<(&'static str, Option<&'static str>)> {
+    match value {
+        Value::Bool(_) => Some(("boolean", None)),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Some(("integer", None)),
+        Value::Number(_) => Some(("number", None)),
+        Value::String(s) if s.is_empty() || (s.starts_with("{{") && s.ends_with("}}")) => None,
+        Value::String(s) if s == "true" || s == "false" => Some(("boolean", None)),
+        Value::String(s) if json_number_type(s) == Some("integer") && s.parse::<i64>().is_ok() => Some(("integer", None)),
+        Value::String(s) if json_number_type(s) == Some("number") => Some(("number", None)),
+        Value::String(s) if is_uuid(s) => Some(("string", Some("uuid"))),
+        Value::String(s) if is_date_time(s) => Some(("string", Some("date-time"))),
+        _ => Some(("string", None)),
+    }
+}
+
+/// A postman value, always a string, as a JSON value of the schema type
+pub(crate) fn typed_example(value: &Value, _type: &str) -> Value {
+    match (value, _type) {
+        (Value::String(s), "integer") if json_number_type(s) == Some("integer") => s.parse::<i64>().map(Value::from).unwrap_or_else(|_| value.clone()),
+        (Value::String(s), "number") if json_number_type(s).is_some() => s.parse::<f64>().ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or_else(|| value.clone()),
+        (Value::String(s), "boolean") => Value::Bool(s == "true"),
+        _ => value.clone(),
+    }
+}
+
+fn infer_primitive(schema: &OpenApi3Schema, samples: &[Value]) -> OpenApi3Schema {
+    let types: Vec<(&Value, (&str, Option<&str>))> = samples.iter()
+        .filter_map(|sample| infer_type(sample).map(|t| (sample, t)))
+        .collect();
+    let first = match types.first() {
+        Some(first) => first,
+        None => return schema.clone(),
+    };
+    let (mut _type, mut format) = first.1;
+    for (_, (other_type, other_format)) in &types[1..] {
+        if *other_type != _type {
+            _type = if (_type == "integer" || _type == "number") && (*other_type == "integer" || *other_type == "number") {
+                "number"
+            } else {
+                "string"
+            };
+        }
+        if *other_format != format {
+            format = None;
+        }
+    }
+    let example = Some(typed_example(schema.example_value().as_ref().unwrap_or(first.0), _type));
+    let format = format.map(str::to_owned);
+    match _type {
+        "integer" => OpenApi3Schema::IntegerType(OpenApi3SchemaIntegerType {
+            _type: _type.to_owned(),
+            format,
+            default: None,
+            example,
+            extensions: BTreeMap::new(),
+        }),
+        "number" => OpenApi3Schema::NumberType(OpenApi3SchemaNumberType {
+            _type: _type.to_owned(),
+            format,
+            default: None,
+            example,
+            extensions: BTreeMap::new(),
+        }),
+        "boolean" => OpenApi3Schema::BooleanType(OpenApi3SchemaBooleanType {
+            _type: _type.to_owned(),
+            default: None,
+            example,
+            extensions: BTreeMap::new(),
+        }),
+        _ => OpenApi3Schema::StringType(OpenApi3SchemaStringType {
+            _type: _type.to_owned(),
+            format,
+            _enum: schema.enum_values(),
+            default: None,
+            example,
+            extensions: BTreeMap::new(),
+        }),
+    }
+}
+
+/// Narrow the `string` schema built from postman values to the type every sample agrees on, array items are
+/// inferred from the elements of the samples
+pub fn infer_schema(schema: &OpenApi3Schema, samples: &[Value]) -> OpenApi3Schema {
+    match schema {
+        OpenApi3Schema::StringType(_) => infer_primitive(schema, samples),
+        OpenApi3Schema::ArrayType(s) => {
+            let elements: Vec<Value> = samples.iter()
+                .flat_map(|sample| match sample {
+                    Value::Array(arr) => arr.clone(),
+                    v => vec![v.clone()],
+                })
+                .collect();
+            let items = infer_primitive(&s.items, &elements);
+            OpenApi3Schema::ArrayType(OpenApi3SchemaArrayType {
+                items: Box::new(items.clone()),
+                example: s.example.as_ref().map(|example| match (example, &items) {
+                    (Value::Array(arr), OpenApi3Schema::IntegerType(i)) => Value::Array(arr.iter().map(|v| typed_example(v, &i._type)).collect()),
+                    (Value::Array(arr), OpenApi3Schema::NumberType(n)) => Value::Array(arr.iter().map(|v| typed_example(v, &n._type)).collect()),
+                    (Value::Array(arr), OpenApi3Schema::BooleanType(_)) => Value::Array(arr.iter().map(|v| typed_example(v, "boolean")).collect()),
+                    _ => example.clone(),
+                }),
+                ..s.clone()
+            })
+        },
+        _ => schema.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parameter(name: &str, _in: &str, style: Option<&str>, explode: Option<bool>) -> OpenApi3Parameter {
+        OpenApi3Parameter {
+            style: style.map(str::to_owned),
+            explode,
+            ..OpenApi3Parameter::new(name, _in, OpenApi3Schema::string(None))
+        }
+    }
+
+    fn entry(key: &str, value: &str) -> QueryEntry {
+        QueryEntry { key: key.to_owned(), value: Some(Value::String(value.to_owned())), disabled: false, description: None }
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn serializes_query_styles() {
+        let ids = json!([1, 2]);
+        assert_eq!(serialize_query(&parameter("ids", "query", None, None), &ids), pairs(&[("ids", "1"), ("ids", "2")]));
+        assert_eq!(serialize_query(&parameter("ids", "query", None, Some(false)), &ids), pairs(&[("ids", "1,2")]));
+        assert_eq!(serialize_query(&parameter("ids", "query", Some("spaceDelimited"), Some(false)), &ids), pairs(&[("ids", "1%202")]));
+        assert_eq!(serialize_query(&parameter("ids", "query", Some("pipeDelimited"), Some(false)), &ids), pairs(&[("ids", "1|2")]));
+        let filter = json!({ "color": "red", "size": 2 });
+        assert_eq!(serialize_query(&parameter("filter", "query", Some("deepObject"), Some(true)), &filter), pairs(&[("filter[color]", "red"), ("filter[size]", "2")]));
+        assert_eq!(serialize_query(&parameter("filter", "query", None, None), &filter), pairs(&[("color", "red"), ("size", "2")]));
+        assert_eq!(serialize_query(&parameter("filter", "query", None, Some(false)), &filter), pairs(&[("filter", "color,red,size,2")]));
+    }
+
+    #[test]
+    fn parses_query_arrays_and_deep_objects() {
+        let params = parse_query(&[entry("ids", "1"), entry("ids", "2"), entry("tags[]", "a"), entry("tags[]", "b"), entry("filter[color]", "red"), entry("q", "x")]);
+        let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["ids", "tags[]", "filter", "q"]);
+        assert_eq!(params[0].example_value(), Some(json!(["1", "2"])));
+        assert_eq!(params[1].example_value(), Some(json!(["a", "b"])));
+        assert_eq!(params[2].style.as_deref(), Some("deepObject"));
+        assert_eq!(params[2].example_value(), Some(json!({ "color": "red" })));
+        assert_eq!(params[3].example_value(), Some(json!("x")));
+
+        let single = parse_query(&[entry("tags[]", "a")]);
+        assert_eq!(single[0].example_value(), Some(json!(["a"])));
+        // what is parsed back serializes to the same entries
+        assert_eq!(serialize_query(&params[1], &params[1].example_value().unwrap()), pairs(&[("tags[]", "a"), ("tags[]", "b")]));
+        assert_eq!(serialize_query(&params[2], &params[2].example_value().unwrap()), pairs(&[("filter[color]", "red")]));
+    }
+
+    #[test]
+    fn parses_disabled_query_entries_as_optional() {
+        let params = parse_query(&[entry("q", "x"), QueryEntry { disabled: true, ..entry("page", "1") }]);
+        assert!(params[0].required);
+        assert!(!params[1].required);
+    }
+
+    #[test]
+    fn splits_generated_enum_choices_off_descriptions() {
+        assert_eq!(parse_description("Sort order\n\nAvailable values: asc, desc"), ("Sort order".to_owned(), Some(vec!["asc".to_owned(), "desc".to_owned()])));
+        assert_eq!(parse_description("Available values: asc"), (String::new(), Some(vec!["asc".to_owned()])));
+        let written = "Available values: depend on the account\n\nSee the docs";
+        assert_eq!(parse_description(written), (written.to_owned(), None));
+        let inline = "Sort order. Available values: asc, desc";
+        assert_eq!(parse_description(inline), (inline.to_owned(), None));
+    }
+
+    #[test]
+    fn serializes_path_styles() {
+        let ids = json!(["a", "b"]);
+        assert_eq!(serialize_path(&parameter("id", "path", None, None), &ids), "a,b");
+        assert_eq!(serialize_path(&parameter("id", "path", Some("label"), None), &ids), ".a,b");
+        assert_eq!(serialize_path(&parameter("id", "path", Some("label"), Some(true)), &ids), ".a.b");
+        assert_eq!(serialize_path(&parameter("id", "path", Some("matrix"), None), &ids), ";id=a,b");
+        assert_eq!(serialize_path(&parameter("id", "path", Some("matrix"), Some(true)), &ids), ";id=a;id=b");
+        assert_eq!(serialize_path(&parameter("id", "path", Some("matrix"), None), &json!("a")), ";id=a");
+        assert_eq!(serialize_path(&parameter("id", "path", Some("label"), None), &json!("a")), ".a");
+    }
+
+    #[test]
+    fn parses_path_styles_back() {
+        for (style, explode, example) in [(Some("label"), None, json!(["a", "b"])), (Some("label"), Some(true), json!(["a", "b"])), (Some("label"), None, json!("a")), (Some("matrix"), None, json!(["a", "b"])), (Some("matrix"), Some(true), json!(["a", "b"])), (Some("matrix"), None, json!("a")), (None, None, json!("a"))] {
+            let written = serialize_path(&parameter("id", "path", style, explode), &example);
+            let parsed = parse_path("id", &written);
+            assert_eq!(parsed.style.as_deref(), style, "{}", written);
+            assert_eq!(parsed.explode, explode, "{}", written);
+            assert_eq!(parsed.example_value(), Some(example), "{}", written);
+            assert!(parsed.required);
+        }
+    }
+
+    #[test]
+    fn recognises_json_numbers_only() {
+        for (value, expected) in [("0", Some("integer")), ("-12", Some("integer")), ("1.5", Some("number")), ("-0.5e-3", Some("number")), ("2E10", Some("number")),
+                                  ("01234", None), ("+1", None), ("1.", None), (".5", None), ("1e", None), ("NaN", None), ("inf", None), ("infinity", None), ("", None)] {
+            assert_eq!(json_number_type(value), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn infers_types_without_losing_values() {
+        let infer = |samples: &[&str]| {
+            let samples: Vec<Value> = samples.iter().map(|s| json!(s)).collect();
+            serde_json::to_value(infer_schema(&OpenApi3Schema::string(None), &samples)).unwrap()
+        };
+        assert_eq!(infer(&["01234", "98101"]), json!({ "type": "string", "example": "01234" }));
+        assert_eq!(infer(&["NaN"]), json!({ "type": "string", "example": "NaN" }));
+        assert_eq!(infer(&["inf", "1"]), json!({ "type": "string", "example": "inf" }));
+        assert_eq!(infer(&["12", "7"]), json!({ "type": "integer", "example": 12 }));
+        assert_eq!(infer(&["12", "0.5"]), json!({ "type": "number", "example": 12.0 }));
+        assert_eq!(infer(&["98765432109876543210"]), json!({ "type": "string", "example": "98765432109876543210" }));
+        assert_eq!(infer(&["9b2e7c1a-2b9f-4a4e-9a59-1d2b4b0c7e11"])["format"], "uuid");
+        assert!(matches!(infer_schema(&OpenApi3Schema::string(None), &[json!("0.5")]), OpenApi3Schema::NumberType(_)));
+    }
+
+    #[test]
+    fn serializes_headers_and_cookies() {
+        assert_eq!(serialize_simple(&json!(["a", "b"]), false), "a,b");
+        assert_eq!(serialize_simple(&json!({ "a": 1 }), false), "a,1");
+        assert_eq!(serialize_simple(&json!({ "a": 1 }), true), "a=1");
+        assert_eq!(serialize_cookie(&parameter("session", "cookie", None, None), &json!("abc")), "session=abc");
+    }
+}
@@ -0,0 +1,544 @@
+use std::collections::BTreeMap;
+use serde_json::value::{Map, Value};
+use serde::de::DeserializeOwned;
+use crate::error::{from_json_str, from_json_value, pointer, Error, Result, Warning};
+use crate::lenient::from_value_lenient;
+use crate::postman_model::{POSTMAN_SCHEMA_2C0, POSTMAN_SCHEMA_2C1, PostmanCollection2c1, PostmanCollectionBody, PostmanCollectionDescription, PostmanCollectionEvent, PostmanCollectionHeader, PostmanCollectionInfo, PostmanCollectionItem, PostmanCollectionRequest, PostmanCollectionResponse, PostmanCollectionUrl, PostmanCollectionVariable, deserialize_url};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostmanVersion {
+    V1,
+    V2c0,
+    V2c1,
+}
+
+impl PostmanVersion {
+    /// Version named by `info.schema`, falling back to the shape of the collection
+    pub fn detect(collection: &Value) -> PostmanVersion {
+        match collection.pointer("/info/schema").and_then(Value::as_str) {
+            Some(schema) if schema.contains("v2.0") => PostmanVersion::V2c0,
+            Some(schema) if schema.contains("v2.1") => PostmanVersion::V2c1,
+            _ if collection.get("info").is_none() && (collection.get("requests").is_some() || collection.get("order").is_some()) => PostmanVersion::V1,
+            _ => PostmanVersion::V2c1,
+        }
+    }
+}
+
+/// Reads a v1, v2.0 or v2.1 collection into the v2.1 model, `source` names the input in errors
+pub fn read_postman_collection(json: &str, source: &str) -> Result<PostmanCollection2c1> {
+    let value: Value = from_json_str(json, source)?;
+    match PostmanVersion::detect(&value) {
+        PostmanVersion::V1 => normalize_v1(from_json_value(value, source)?, source),
+        PostmanVersion::V2c0 => normalize_2c0(from_json_value(value, source)?, source),
+        PostmanVersion::V2c1 => from_json_value(value, source),
+    }
+}
+
+/// Deserializes an element rebuilt in the v2.1 shape, its errors point at `pointer`, the element of the input it
+/// was rebuilt from, as what is left failing is a url or event shape the v2.1 model cannot hold
+fn normalized<T: DeserializeOwned>(value: Value, source: &str, pointer: String) -> Result<T> {
+    serde_json::from_value(value).map_err(|err| Error::Parse {
+        source: source.to_owned(),
+        pointer,
+        message: err.to_string(),
+    })
+}
+
+/// Same as `read_postman_collection`, dropping the elements that fail to parse instead of failing
+pub fn read_postman_collection_lenient(json: &str, source: &str) -> Result<(PostmanCollection2c1, Vec<Warning>)> {
+    let value: Value = from_json_str(json, source)?;
+    let mut warnings: Vec<Warning> = Vec::new();
+    let collection = match PostmanVersion::detect(&value) {
+        PostmanVersion::V1 => normalize_v1(from_value_lenient(value, source, &mut warnings)?, source)?,
+        PostmanVersion::V2c0 => normalize_2c0(from_value_lenient(value, source, &mut warnings)?, source)?,
+        PostmanVersion::V2c1 => from_value_lenient(value, source, &mut warnings)?,
+    };
+    Ok((collection, warnings))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollection2c0 {
+    pub info: PostmanCollectionInfo,
+    pub item: Vec<PostmanCollection2c0Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<Vec<PostmanCollectionEvent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable: Option<Vec<PostmanCollectionVariable>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollection2c0Item {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item: Option<Vec<PostmanCollection2c0Item>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<PostmanCollection2c0Request>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<Vec<PostmanCollectionResponse>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<Vec<PostmanCollectionEvent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Value>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollection2c0Request {
+    /// Auth parameters are objects keyed by name, `{"bearer": {"token": "..."}}`, instead of v2.1 key/value lists
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Value>,
+    pub method: String,
+    pub header: Vec<PostmanCollectionHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<PostmanCollectionBody>,
+    #[serde(deserialize_with = "deserialize_url")]
+    pub url: PostmanCollectionUrl,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<PostmanCollectionDescription>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+/// `{"type": "bearer", "bearer": {"token": "t"}}` into `{"type": "bearer", "bearer": [{"key": "token", "value": "t", "type": "string"}]}`
+fn auth_2c0_to_2c1(auth: Value) -> Value {
+    match auth {
+        Value::Object(obj) => Value::Object(obj.into_iter().map(|(key, value)| match value {
+            Value::Object(params) if key != "type" => {
+                let list = params.into_iter().map(|(k, v)| {
+                    let mut item = Map::new();
+                    item.insert("key".to_owned(), Value::String(k));
+                    item.insert("value".to_owned(), v);
+                    item.insert("type".to_owned(), Value::String("string".to_owned()));
+                    Value::Object(item)
+                }).collect();
+                (key, Value::Array(list))
+            },
+            value => (key, value),
+        }).collect()),
+        auth => auth,
+    }
+}
+fn auth_2c1_to_2c0(auth: Value) -> Value {
+    match auth {
+        Value::Object(obj) => Value::Object(obj.into_iter().map(|(key, value)| match value {
+            Value::Array(list) if key != "type" => {
+                let params = list.into_iter().filter_map(|item| {
+                    let key = item.get("key")?.as_str()?.to_owned();
+                    Some((key, item.get("value").cloned().unwrap_or(Value::Null)))
+                }).collect();
+                (key, Value::Object(params))
+            },
+            value => (key, value),
+        }).collect()),
+        auth => auth,
+    }
+}
+/// `location` is the JSON pointer of `item` in the collection
+fn item_2c0_to_2c1(item: PostmanCollection2c0Item, source: &str, location: &str) -> Result<PostmanCollectionItem> {
+    let request = match item.request {
+        Some(request) => Some(PostmanCollectionRequest {
+            auth: request.auth.map(|auth| normalized(auth_2c0_to_2c1(auth), source, format!("{}/request/auth", location))).transpose()?,
+            method: request.method,
+            header: request.header,
+            body: request.body,
+            url: request.url,
+            description: request.description,
+            extensions: request.extensions,
+        }),
+        None => None,
+    };
+    Ok(PostmanCollectionItem {
+        name: item.name,
+        item: item.item.map(|items| items.into_iter().enumerate().map(|(index, item)| item_2c0_to_2c1(item, source, &format!("{}/item/{}", location, index))).collect()).transpose()?,
+        request,
+        response: item.response,
+        event: item.event,
+        auth: item.auth.map(|auth| normalized(auth_2c0_to_2c1(auth), source, format!("{}/auth", location))).transpose()?,
+        extensions: item.extensions,
+    })
+}
+fn item_2c1_to_2c0(item: PostmanCollectionItem) -> PostmanCollection2c0Item {
+    PostmanCollection2c0Item {
+        name: item.name,
+        item: item.item.map(|items| items.into_iter().map(item_2c1_to_2c0).collect()),
+        request: item.request.map(|request| PostmanCollection2c0Request {
+            auth: request.auth.and_then(|auth| serde_json::to_value(auth).ok()).map(auth_2c1_to_2c0),
+            method: request.method,
+            header: request.header,
+            body: request.body,
+            url: request.url,
+            description: request.description,
+            extensions: request.extensions,
+        }),
+        response: item.response,
+        event: item.event,
+        auth: item.auth.and_then(|auth| serde_json::to_value(auth).ok()).map(auth_2c1_to_2c0),
+        extensions: item.extensions,
+    }
+}
+/// `source` names the input in errors
+pub fn normalize_2c0(collection: PostmanCollection2c0, source: &str) -> Result<PostmanCollection2c1> {
+    Ok(PostmanCollection2c1 {
+        info: PostmanCollectionInfo {
+            schema: POSTMAN_SCHEMA_2C1.to_owned(),
+            ..collection.info
+        },
+        item: collection.item.into_iter().enumerate().map(|(index, item)| item_2c0_to_2c1(item, source, &format!("/item/{}", index))).collect::<Result<_>>()?,
+        event: collection.event,
+        auth: collection.auth.map(|auth| normalized(auth_2c0_to_2c1(auth), source, String::from("/auth"))).transpose()?,
+        variable: collection.variable,
+        extensions: collection.extensions,
+    })
+}
+pub fn to_postman_collection_2c0(collection: PostmanCollection2c1) -> PostmanCollection2c0 {
+    PostmanCollection2c0 {
+        info: PostmanCollectionInfo {
+            schema: POSTMAN_SCHEMA_2C0.to_owned(),
+            ..collection.info
+        },
+        item: collection.item.into_iter().map(item_2c1_to_2c0).collect(),
+        event: collection.event,
+        auth: collection.auth.and_then(|auth| serde_json::to_value(auth).ok()).map(auth_2c1_to_2c0),
+        variable: collection.variable,
+        extensions: collection.extensions,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionV1 {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub order: Vec<String>,
+    #[serde(default)]
+    pub folders: Vec<PostmanCollectionV1Folder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folders_order: Option<Vec<String>>,
+    #[serde(default)]
+    pub requests: Vec<PostmanCollectionV1Request>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionV1Folder {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub order: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folders_order: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionV1Request {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<String>,
+    #[serde(rename = "headerData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_data: Option<Vec<PostmanCollectionV1KeyValue>>,
+    #[serde(rename = "queryParams")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_params: Option<Vec<PostmanCollectionV1KeyValue>>,
+    #[serde(rename = "pathVariableData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_variable_data: Option<Vec<PostmanCollectionV1KeyValue>>,
+    #[serde(rename = "dataMode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_mode: Option<String>,
+    #[serde(rename = "rawModeData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_mode_data: Option<String>,
+    /// Fields of the `params` (formdata) and `urlencoded` modes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<PostmanCollectionV1KeyValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "currentHelper")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_helper: Option<String>,
+    #[serde(rename = "helperAttributes")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub helper_attributes: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests: Option<String>,
+    #[serde(rename = "preRequestScript")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_request_script: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responses: Option<Vec<PostmanCollectionV1Response>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionV1KeyValue {
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// `text` or `file` for `params` fields
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionV1Response {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "responseCode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_code: Option<PostmanCollectionV1ResponseCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionV1ResponseCode {
+    pub code: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+fn key_value(key: &str, value: &str, description: &Option<String>, enabled: Option<bool>) -> Value {
+    let mut obj = Map::new();
+    obj.insert("key".to_owned(), Value::String(key.to_owned()));
+    obj.insert("value".to_owned(), Value::String(value.to_owned()));
+    if let Some(description) = description {
+        obj.insert("description".to_owned(), Value::String(description.clone()));
+    }
+    if enabled == Some(false) {
+        obj.insert("disabled".to_owned(), Value::Bool(true));
+    }
+    Value::Object(obj)
+}
+/// v1 headers are either `headerData` or `Key: value` lines, where a `//` prefix disables the header
+fn v1_headers(request: &PostmanCollectionV1Request) -> Vec<Value> {
+    if let Some(header_data) = &request.header_data {
+        return header_data.iter()
+            .map(|h| key_value(&h.key, h.value.as_deref().unwrap_or(""), &h.description, h.enabled))
+            .collect();
+    }
+    request.headers.as_deref().unwrap_or("").lines().filter_map(|line| {
+        let (line, enabled) = match line.trim().strip_prefix("//") {
+            Some(disabled) => (disabled, Some(false)),
+            None => (line.trim(), None),
+        };
+        let mut pair = line.splitn(2, ':');
+        let key = pair.next()?.trim();
+        if key.is_empty() {
+            return None;
+        }
+        Some(key_value(key, pair.next().unwrap_or("").trim(), &None, enabled))
+    }).collect()
+}
+/// Splits a v1 url string into the v2.1 `raw`/`host`/`path`/`query`/`variable` parts
+fn v1_url(request: &PostmanCollectionV1Request) -> Value {
+    let raw = request.url.clone();
+    let (address, query_string) = match raw.find('?') {
+        Some(index) => (&raw[..index], Some(&raw[index + 1..])),
+        None => (&raw[..], None),
+    };
+    let mut obj = Map::new();
+    let address = match address.find("://") {
+        Some(index) => {
+            obj.insert("protocol".to_owned(), Value::String(address[..index].to_owned()));
+            &address[index + 3..]
+        },
+        None => address,
+    };
+    let mut segments = address.split('/');
+    let host: Vec<Value> = segments.next().into_iter().map(|h| Value::String(h.to_owned())).collect();
+    let path: Vec<String> = segments.map(|s| s.to_owned()).collect();
+    let query: Vec<Value> = match &request.query_params {
+        Some(params) => params.iter()
+            .map(|q| key_value(&q.key, q.value.as_deref().unwrap_or(""), &q.description, q.enabled))
+            .collect(),
+        None => query_string.unwrap_or("").split('&').filter(|pair| !pair.is_empty()).map(|pair| {
+            let mut kv = pair.splitn(2, '=');
+            key_value(kv.next().unwrap_or(""), kv.next().unwrap_or(""), &None, None)
+        }).collect(),
+    };
+    let variable: Vec<Value> = path.iter().filter_map(|segment| segment.strip_prefix(':')).map(|name| {
+        let data = request.path_variable_data.iter().flatten().find(|v| v.key == name);
+        key_value(name, data.and_then(|v| v.value.as_deref()).unwrap_or(""), &data.and_then(|v| v.description.clone()), None)
+    }).collect();
+    obj.insert("raw".to_owned(), Value::String(raw.clone()));
+    obj.insert("host".to_owned(), Value::Array(host));
+    obj.insert("path".to_owned(), Value::Array(path.into_iter().map(Value::String).collect()));
+    if !query.is_empty() {
+        obj.insert("query".to_owned(), Value::Array(query));
+    }
+    if !variable.is_empty() {
+        obj.insert("variable".to_owned(), Value::Array(variable));
+    }
+    Value::Object(obj)
+}
+fn v1_auth(request: &PostmanCollectionV1Request) -> Option<Value> {
+    let attributes = request.helper_attributes.as_ref()?;
+    let (auth_type, keys): (&str, &[&str]) = match request.current_helper.as_deref()? {
+        "bearerAuth" => ("bearer", &["token"]),
+        "basicAuth" => ("basic", &["username", "password"]),
+        _ => return None,
+    };
+    let mut params = Map::new();
+    for key in keys {
+        params.insert((*key).to_owned(), attributes.get(*key).cloned().unwrap_or_else(|| Value::String(String::new())));
+    }
+    let mut auth = Map::new();
+    auth.insert("type".to_owned(), Value::String(auth_type.to_owned()));
+    auth.insert(auth_type.to_owned(), Value::Object(params));
+    Some(auth_2c0_to_2c1(Value::Object(auth)))
+}
+fn v1_event(listen: &str, script: &Option<String>) -> Option<Value> {
+    let script = script.as_ref().filter(|s| !s.trim().is_empty())?;
+    let mut inner = Map::new();
+    inner.insert("type".to_owned(), Value::String("text/javascript".to_owned()));
+    inner.insert("exec".to_owned(), Value::Array(script.lines().map(|l| Value::String(l.to_owned())).collect()));
+    let mut event = Map::new();
+    event.insert("listen".to_owned(), Value::String(listen.to_owned()));
+    event.insert("script".to_owned(), Value::Object(inner));
+    Some(Value::Object(event))
+}
+/// `data` fields as v2.1 `formdata`/`urlencoded` fields, `enabled: false` becoming `disabled: true`
+fn v1_body_fields(request: &PostmanCollectionV1Request) -> Value {
+    Value::Array(request.data.iter().flatten().map(|field| {
+        let mut obj = Map::new();
+        obj.insert("key".to_owned(), Value::String(field.key.clone()));
+        obj.insert("value".to_owned(), Value::String(field.value.clone().unwrap_or_default()));
+        if let Some(_type) = &field._type {
+            obj.insert("type".to_owned(), Value::String(_type.clone()));
+        }
+        if field.enabled == Some(false) {
+            obj.insert("disabled".to_owned(), Value::Bool(true));
+        }
+        Value::Object(obj)
+    }).collect())
+}
+/// `index` is the position of `request` in `requests`
+fn request_v1_to_2c1(request: &PostmanCollectionV1Request, index: usize, source: &str) -> Result<PostmanCollectionItem> {
+    let location = pointer(&["requests", &index.to_string()]);
+    let mut obj = Map::new();
+    obj.insert("method".to_owned(), Value::String(request.method.clone()));
+    obj.insert("header".to_owned(), Value::Array(v1_headers(request)));
+    obj.insert("url".to_owned(), v1_url(request));
+    if let Some(description) = &request.description {
+        obj.insert("description".to_owned(), Value::String(description.clone()));
+    }
+    if let Some(auth) = v1_auth(request) {
+        obj.insert("auth".to_owned(), auth);
+    }
+    // a `binary` body only names a file on the machine that sent it, it becomes the v2.1 `file` mode, reported as
+    // unsupported with the request it belongs to when converting
+    let body = match request.data_mode.as_deref() {
+        Some("raw") => request.raw_mode_data.as_ref().map(|raw| ("raw", Value::String(raw.clone()))),
+        Some("params") => Some(("formdata", v1_body_fields(request))),
+        Some("urlencoded") => Some(("urlencoded", v1_body_fields(request))),
+        Some("binary") => Some(("file", Value::Object(Map::new()))),
+        _ => None,
+    };
+    if let Some((mode, fields)) = body {
+        let mut body = Map::new();
+        body.insert("mode".to_owned(), Value::String(mode.to_owned()));
+        body.insert(mode.to_owned(), fields);
+        obj.insert("body".to_owned(), Value::Object(body));
+    }
+    let events: Vec<Value> = v1_event("prerequest", &request.pre_request_script).into_iter()
+        .chain(v1_event("test", &request.tests))
+        .collect();
+    let response = request.responses.as_ref().map(|responses| responses.iter().map(|r| PostmanCollectionResponse {
+        name: r.name.clone(),
+        status: r.response_code.as_ref().and_then(|c| c.name.clone()),
+        code: r.response_code.as_ref().map(|c| c.code),
+        body: r.text.clone().unwrap_or_default(),
+        extensions: BTreeMap::new(),
+    }).collect());
+    Ok(PostmanCollectionItem {
+        name: request.name.clone(),
+        item: None,
+        request: Some(normalized(Value::Object(obj), source, location.clone())?),
+        response,
+        event: if !events.is_empty() {
+            Some(normalized(Value::Array(events), source, location)?)
+        } else {
+            None
+        },
+        auth: None,
+        extensions: BTreeMap::new(),
+    })
+}
+fn v1_items(collection: &PostmanCollectionV1, order: &[String], folders_order: &[String], used: &mut Vec<String>, source: &str) -> Result<Vec<PostmanCollectionItem>> {
+    let mut items = Vec::new();
+    for folder_id in folders_order {
+        if let Some((index, folder)) = collection.folders.iter().enumerate().find(|(_, f)| &f.id == folder_id) {
+            if used.contains(&folder.id) {
+                return Err(Error::Parse {
+                    source: source.to_owned(),
+                    pointer: pointer(&["folders", &index.to_string()]),
+                    message: format!("folder `{}` is listed more than once in `folders_order`", folder.id),
+                });
+            }
+            used.push(folder.id.clone());
+            let nested = folder.folders_order.clone().unwrap_or_default();
+            items.push(PostmanCollectionItem {
+                name: folder.name.clone(),
+                item: Some(v1_items(collection, &folder.order, &nested, used, source)?),
+                request: None,
+                response: None,
+                event: None,
+                auth: None,
+                extensions: BTreeMap::new(),
+            });
+        }
+    }
+    for request_id in order {
+        if let Some((index, request)) = collection.requests.iter().enumerate().find(|(_, r)| &r.id == request_id) {
+            used.push(request.id.clone());
+            items.push(request_v1_to_2c1(request, index, source)?);
+        }
+    }
+    Ok(items)
+}
+/// Rebuilds the folder tree from the `order`/`folders_order` ids, requests no folder lists end up at the root.
+/// `source` names the input in errors
+pub fn normalize_v1(collection: PostmanCollectionV1, source: &str) -> Result<PostmanCollection2c1> {
+    let nested: Vec<&String> = collection.folders.iter().flat_map(|f| f.folders_order.iter().flatten()).collect();
+    let folders_order = collection.folders_order.clone().unwrap_or_else(|| {
+        collection.folders.iter().map(|f| f.id.clone()).filter(|id| !nested.contains(&id)).collect()
+    });
+    let mut used: Vec<String> = Vec::new();
+    let mut item = v1_items(&collection, &collection.order, &folders_order, &mut used, source)?;
+    for (index, request) in collection.requests.iter().enumerate().filter(|(_, r)| !used.contains(&r.id)) {
+        item.push(request_v1_to_2c1(request, index, source)?);
+    }
+    Ok(PostmanCollection2c1 {
+        info: PostmanCollectionInfo {
+            name: collection.name,
+            description: collection.description.map(Into::into),
+            schema: POSTMAN_SCHEMA_2C1.to_owned(),
+            extensions: BTreeMap::new(),
+        },
+        item,
+        event: None,
+        auth: None,
+        variable: None,
+        extensions: BTreeMap::new(),
+    })
+}
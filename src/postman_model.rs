@@ -1,18 +1,36 @@
 use std::collections::BTreeMap;
+use schemars::JsonSchema;
 use serde_json::value::Value;
-use crate::model::{OpenApi3, OpenApi3Schema, OpenApi3ConvertOption, OpenApi3OperationMethodRequestBodyContent, OpenApi3OperationMethodRequestBodyJson, OpenApi3OperationMethodRequestBody, OpenApi3Parameter, OpenApi3SchemaStringType, OpenApi3SchemaObjectType, OpenApi3OperationMethodSecurity};
+use crate::parameter_style::{parse_query, QueryEntry, ENUM_DESCRIPTION_PREFIX, serialize_cookie, serialize_path, serialize_query, serialize_simple};
+use crate::split::PostmanSplit;
+use crate::error::{pointer, tolerate, Error, Result, Warning, WarningKind};
+use crate::model::{x_extensions, OpenApi3Example, OpenApi3ExampleOrRef, OpenApi3ParameterOrRef, OpenApi3PostmanFolder, OPERATION_ID_KEY, ROUND_TRIP_DOCUMENT_KEY, ROUND_TRIP_OPERATION_KEY, OpenApi3, OpenApi3RoundTripOperation, OpenApi3Components, OpenApi3OperationMethod, OpenApi3Schema, OpenApi3ConvertOption, OpenApi3OperationMethodRequestBodyContent, OpenApi3MediaType, OpenApi3OperationMethodRequestBody, OpenApi3Parameter, OpenApi3SchemaObjectType, OpenApi3SchemaStringType, OpenApi3OperationMethodSecurity};
+
+pub const POSTMAN_SCHEMA_2C0: &str = "https://schema.getpostman.com/json/collection/v2.0.0/collection.json";
+pub const POSTMAN_SCHEMA_2C1: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollection2c1 {
     pub info: PostmanCollectionInfo,
-    pub item: Vec<PostmanCollectionItem>
+    pub item: Vec<PostmanCollectionItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<Vec<PostmanCollectionEvent>>,
+    /// Auth inherited by the requests without one of their own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<PostmanCollectionAuth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable: Option<Vec<PostmanCollectionVariable>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionInfo {
     pub name: String,
-    pub description: Option<String>,
+    pub description: Option<PostmanCollectionDescription>,
     pub schema: String,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct PostmanCollectionItemInner {
@@ -23,79 +41,188 @@ pub struct PostmanCollectionInfo {
 pub struct PostmanCollectionItem {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub item: Option<Box<Vec<PostmanCollectionItem>>>,
+    pub item: Option<Vec<PostmanCollectionItem>>,
     pub request: Option<PostmanCollectionRequest>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub response: Option<Vec<PostmanCollectionResponse>>
+    pub response: Option<Vec<PostmanCollectionResponse>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<Vec<PostmanCollectionEvent>>,
+    /// Auth of a folder, inherited by the requests in it without one of their own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<PostmanCollectionAuth>,
     // request: PostmanCollectionRequest,
     // response: Vec<serde_json::Value>,
     // #[serde(rename = "protocolProfileBehavior")]
     // protocol_profile_behavior: serde_json::Value
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionEvent {
+    pub listen: String,
+    pub script: PostmanCollectionScript,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionScript {
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub exec: Vec<String>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub status: Option<String>,
     pub code: Option<u32>,
     pub body: String,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub auth: Option<PostmanCollectionRequestAuth>,
+    pub auth: Option<PostmanCollectionAuth>,
     pub method: String,
     pub header: Vec<PostmanCollectionHeader>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body: Option<PostmanCollectionBody>,
+    #[serde(deserialize_with = "deserialize_url")]
     pub url: PostmanCollectionUrl,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
+    pub description: Option<PostmanCollectionDescription>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
+/// An auth, kept as is when its shape is not one the model reads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PostmanCollectionAuth {
+    Known(PostmanCollectionRequestAuth),
+    Raw(Value),
+}
+
+impl PostmanCollectionAuth {
+    /// The `type` of the auth, empty when it has none
+    pub fn auth_type(&self) -> &str {
+        match self {
+            PostmanCollectionAuth::Known(auth) => &auth._type,
+            PostmanCollectionAuth::Raw(auth) => auth.get("type").and_then(Value::as_str).unwrap_or_default(),
+        }
+    }
+}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionRequestAuth {
     #[serde(rename = "type")]
-    _type: String,
-    bearer: Option<Vec<PostmanCollectionRequestAuthItem>>,
-    basic: Option<Vec<PostmanCollectionRequestAuthItem>>
+    pub _type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bearer: Option<Vec<PostmanCollectionRequestAuthItem>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub basic: Option<Vec<PostmanCollectionRequestAuthItem>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionRequestAuthItem {
-    key: String,
-    value: String,
+    pub key: String,
+    pub value: String,
     #[serde(rename = "type")]
-    _type: String,
+    pub _type: String,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 impl PostmanCollectionRequest {
-    pub fn oa3_get_securities(&self) -> OpenApi3OperationMethodSecurity {
-        if let Some(auth) = &self.auth {
-            let mut bmap = BTreeMap::new();
+    /// Security requirements from the request auth, an `Authorization` header or an api key header
+    pub fn oa3_get_securities(&self, convert_option: &OpenApi3ConvertOption) -> OpenApi3OperationMethodSecurity {
+        let mut bmap = BTreeMap::new();
+        if let Some(PostmanCollectionAuth::Known(auth)) = &self.auth {
             if auth.bearer.is_some() {
                 bmap.insert(auth._type.clone(), Vec::new());
             }
+        }
+        if !convert_option.keep_transport_headers {
+            for headeritem in self.enabled_headers() {
+                if headeritem.key.to_lowercase() == "authorization" {
+                    bmap.insert("bearer".to_owned(), Vec::new());
+                } else if convert_option.is_api_key_header(&headeritem.key) {
+                    bmap.insert(headeritem.key.clone(), Vec::new());
+                }
+            }
+        }
+        if self.auth.is_some() || !bmap.is_empty() {
             Some(vec![bmap])
         } else {
             None
         }
     }
-    pub fn oa3_get_request_body(&self) -> Option<OpenApi3OperationMethodRequestBody> {
-        if let Some(body) = &self.body {
-            Some(OpenApi3OperationMethodRequestBody {
-                content: OpenApi3OperationMethodRequestBodyContent::ApplicationJson(OpenApi3OperationMethodRequestBodyJson {
-                    schema: OpenApi3Schema::ObjectType(OpenApi3SchemaObjectType {
-                        properties: BTreeMap::new(),
-                        example: None,
-                    }),
-                    example: Some(Value::String(body.raw.clone())),
-                })
-            })
-        } else {
-            None
-        }
+    /// `pointer` locates the request in the collection, for the error raised on bodies of an unknown mode.
+    /// Form bodies become object schemas of their fields, a graphql body the JSON object it is posted as.
+    pub fn oa3_get_request_body(&self, pointer: &str) -> Result<Option<OpenApi3OperationMethodRequestBody>> {
+        let body = match &self.body {
+            Some(body) => body,
+            None => return Ok(None),
+        };
+        let (media_type, properties, example) = match body.mode.as_str() {
+            "raw" => (self.media_type("content-type"), BTreeMap::new(), Value::String(body.raw.clone())),
+            "formdata" => {
+                let (properties, example) = form_fields(body.formdata.as_deref().unwrap_or_default());
+                (String::from("multipart/form-data"), properties, example)
+            },
+            "urlencoded" => {
+                let (properties, example) = form_fields(body.urlencoded.as_deref().unwrap_or_default());
+                (String::from("application/x-www-form-urlencoded"), properties, example)
+            },
+            "graphql" => {
+                let graphql = body.graphql.as_ref();
+                let query = graphql.map(|graphql| graphql.query.clone()).unwrap_or_default();
+                let variables = graphql.and_then(|graphql| graphql.variables.as_deref())
+                    .filter(|variables| !variables.trim().is_empty())
+                    .map(|variables| serde_json::from_str(variables).unwrap_or_else(|_| Value::String(variables.to_owned())))
+                    .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+                let mut properties: BTreeMap<String, Box<OpenApi3Schema>> = BTreeMap::new();
+                properties.insert("query".to_owned(), Box::new(string_schema(None)));
+                properties.insert("variables".to_owned(), Box::new(object_schema(BTreeMap::new())));
+                (self.media_type("content-type"), properties, serde_json::json!({ "query": query, "variables": variables }))
+            },
+            mode => return Err(Error::Unsupported {
+                pointer: format!("{}/body/mode", pointer),
+                message: format!("`{}` request body", mode),
+            }),
+        };
+        let mut content: OpenApi3OperationMethodRequestBodyContent = BTreeMap::new();
+        content.insert(media_type, OpenApi3MediaType {
+            schema: Some(object_schema(properties)),
+            example: Some(example),
+            examples: None,
+            extensions: BTreeMap::new(),
+        });
+        Ok(Some(OpenApi3OperationMethodRequestBody {
+            content,
+            extensions: BTreeMap::new(),
+        }))
+    }
+    fn enabled_headers(&self) -> impl Iterator<Item = &PostmanCollectionHeader> {
+        self.header.iter().filter(|headeritem| !headeritem.disabled.unwrap_or(false))
+    }
+    /// Media type named by the `Content-Type` or `Accept` header, without its parameters
+    pub fn media_type(&self, header_name: &str) -> String {
+        self.enabled_headers()
+            .find(|headeritem| headeritem.key.to_lowercase() == header_name)
+            .and_then(|headeritem| headeritem.value.split(',').next())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty() && value != "*/*")
+            .unwrap_or_else(|| String::from("application/json"))
     }
     pub fn oa3_get_parameters_for_header_query(&self, convert_option: &OpenApi3ConvertOption) -> Vec<OpenApi3Parameter> {
         let mut params: Vec<OpenApi3Parameter> = Vec::new();
         if let Some(urlquery) = &self.url.query {
+            let mut queries: Vec<QueryEntry> = Vec::new();
             for query in urlquery {
                 let example = match &query.value {
                     Value::String(s) => {
@@ -109,21 +236,31 @@ impl PostmanCollectionRequest {
                     },
                     _ => None,
                 };
-                params.push(OpenApi3Parameter {
-                    description: String::from(""),
-                    _in: String::from("query"),
-                    name: query.key.clone(),
-                    required: false,
-                    schema: OpenApi3Schema::StringType(OpenApi3SchemaStringType {
-                        _type: String::from("string"),
-                        _enum: None,
-                        default: None,
-                        example: example,
-                    })
+                queries.push(QueryEntry {
+                    key: query.key.clone(),
+                    value: example,
+                    disabled: query.disabled.unwrap_or(false),
+                    description: query.description.as_ref().map(|d| d.text().to_owned()),
                 });
             }
+            params.extend(parse_query(&queries));
         }
-        for headeritem in &self.header {
+        for headeritem in self.enabled_headers() {
+            if convert_option.is_ignored_header(&headeritem.key) {
+                continue;
+            }
+            if headeritem.key.to_lowercase() == "cookie" {
+                for cookie in headeritem.value.split(';') {
+                    let mut pair = cookie.trim().splitn(2, '=');
+                    let name = pair.next().unwrap_or("");
+                    if name.is_empty() {
+                        continue;
+                    }
+                    let example = pair.next().map(|value| Value::String(value.to_owned()));
+                    params.push(OpenApi3Parameter::new(name, "cookie", OpenApi3Schema::string(example)));
+                }
+                continue;
+            }
             let mut val = Some(serde_json::value::Value::String(headeritem.value.clone()));
             for map in &convert_option.mapping {
                 if map.key == headeritem.key {
@@ -131,70 +268,249 @@ impl PostmanCollectionRequest {
                 }
             }
             params.push(OpenApi3Parameter {
-                description: String::from(""),
-                _in: String::from("header"),
-                name: headeritem.key.clone(),
-                required: false,
-                schema: OpenApi3Schema::StringType(OpenApi3SchemaStringType {
-                    _type: String::from("string"),
-                    _enum: None,
-                    default: None,
-                    example: val,
-                })
+                description: headeritem.description.as_ref().map(|d| d.text().to_owned()).unwrap_or_default(),
+                ..OpenApi3Parameter::new(&headeritem.key, "header", OpenApi3Schema::string(val))
             });
         }
         params
     }
 }
+/// A description, plain text or a `{content, type}` object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PostmanCollectionDescription {
+    Text(String),
+    Content {
+        content: String,
+        /// `text/markdown` or `text/plain`
+        #[serde(rename = "type")]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        _type: Option<String>,
+        #[serde(flatten)]
+        extensions: BTreeMap<String, Value>,
+    },
+}
+
+impl PostmanCollectionDescription {
+    pub fn text(&self) -> &str {
+        match self {
+            PostmanCollectionDescription::Text(text) => text,
+            PostmanCollectionDescription::Content { content, .. } => content,
+        }
+    }
+}
+
+impl From<String> for PostmanCollectionDescription {
+    fn from(text: String) -> PostmanCollectionDescription {
+        PostmanCollectionDescription::Text(text)
+    }
+}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionHeader {
-    key: String,
+    pub key: String,
     #[serde(rename = "type")]
-    _type: Option<String>,
-    value: String,
+    pub _type: Option<String>,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<PostmanCollectionDescription>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionBody {
-    mode: String,
-    raw: String,
+    pub mode: String,
+    #[serde(default)]
+    pub raw: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formdata: Option<Vec<PostmanCollectionBodyField>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub urlencoded: Option<Vec<PostmanCollectionBodyField>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    options: Option<PostmanCollectionBodyOptions>
+    pub graphql: Option<PostmanCollectionBodyGraphql>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<PostmanCollectionBodyOptions>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+/// A `formdata` or `urlencoded` field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionBodyField {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+    /// `text` or `file`, formdata only
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollectionBodyGraphql {
+    #[serde(default)]
+    pub query: String,
+    /// The variables as the JSON text typed in postman
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<String>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+fn string_schema(format: Option<String>) -> OpenApi3Schema {
+    OpenApi3Schema::StringType(OpenApi3SchemaStringType {
+        _type: String::from("string"),
+        format,
+        _enum: None,
+        default: None,
+        example: None,
+        extensions: BTreeMap::new(),
+    })
+}
+
+fn object_schema(properties: BTreeMap<String, Box<OpenApi3Schema>>) -> OpenApi3Schema {
+    OpenApi3Schema::ObjectType(OpenApi3SchemaObjectType {
+        _type: Some(String::from("object")),
+        properties,
+        example: None,
+        extensions: BTreeMap::new(),
+    })
+}
+
+/// String properties of the enabled fields, `binary` for files, and the object of their values
+fn form_fields(fields: &[PostmanCollectionBodyField]) -> (BTreeMap<String, Box<OpenApi3Schema>>, Value) {
+    let mut properties: BTreeMap<String, Box<OpenApi3Schema>> = BTreeMap::new();
+    let mut example = serde_json::Map::new();
+    for field in fields.iter().filter(|field| !field.disabled.unwrap_or(false)) {
+        if field._type.as_deref() == Some("file") {
+            properties.insert(field.key.clone(), Box::new(string_schema(Some(String::from("binary")))));
+        } else {
+            properties.insert(field.key.clone(), Box::new(string_schema(None)));
+            example.insert(field.key.clone(), Value::String(field.value.clone()));
+        }
+    }
+    (properties, Value::Object(example))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionBodyOptions {
-    raw: PostmanCollectionBodyOptionsRaw
+    pub raw: PostmanCollectionBodyOptionsRaw,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionBodyOptionsRaw {
     #[serde(skip_serializing_if = "Option::is_none")]
-    language: Option<String>,
+    pub language: Option<String>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionVariable {
     pub key: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_variable_value")]
     pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<PostmanCollectionDescription>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionUrl {
-    raw: String,
-    host: Vec<String>,
-    path: Vec<String>,
+    pub raw: String,
+    pub host: Vec<String>,
+    pub path: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    query: Option<Vec<PostmanCollectionUrlQuery>>,
+    pub query: Option<Vec<PostmanCollectionUrlQuery>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    variable: Option<Vec<PostmanCollectionVariable>>
+    pub variable: Option<Vec<PostmanCollectionVariable>>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+/// A request url, the raw string form or the parsed object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PostmanCollectionUrlForm {
+    Raw(String),
+    Url(PostmanCollectionUrl),
+}
+
+/// Reads either url form, a raw string is split into its host, path and query
+pub(crate) fn deserialize_url<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<PostmanCollectionUrl, D::Error> {
+    match serde::Deserialize::deserialize(deserializer)? {
+        PostmanCollectionUrlForm::Raw(raw) => Ok(PostmanCollectionUrl::from(raw)),
+        PostmanCollectionUrlForm::Url(url) => Ok(url),
+    }
+}
+
+/// Reads a variable value, postman also saves numbers and booleans unquoted
+fn deserialize_variable_value<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    match serde::Deserialize::deserialize(deserializer)? {
+        Value::String(value) => Ok(value),
+        Value::Null => Ok(String::new()),
+        value => Ok(value.to_string()),
+    }
+}
+
+impl From<String> for PostmanCollectionUrl {
+    fn from(raw: String) -> PostmanCollectionUrl {
+        let (address, query_string) = match raw.find('?') {
+            Some(index) => (&raw[..index], Some(&raw[index + 1..])),
+            None => (&raw[..], None),
+        };
+        let mut extensions: BTreeMap<String, Value> = BTreeMap::new();
+        let address = match address.find("://") {
+            Some(index) => {
+                extensions.insert("protocol".to_owned(), Value::String(address[..index].to_owned()));
+                &address[index + 3..]
+            },
+            None => address,
+        };
+        let mut segments = address.split('/');
+        let host: Vec<String> = segments.next().into_iter().filter(|h| !h.is_empty()).map(str::to_owned).collect();
+        let path: Vec<String> = segments.map(str::to_owned).collect();
+        let query: Vec<PostmanCollectionUrlQuery> = query_string.unwrap_or("").split('&').filter(|pair| !pair.is_empty()).map(|pair| {
+            let mut kv = pair.splitn(2, '=');
+            PostmanCollectionUrlQuery {
+                key: kv.next().unwrap_or("").to_owned(),
+                value: Value::String(kv.next().unwrap_or("").to_owned()),
+                disabled: None,
+                description: None,
+                extensions: BTreeMap::new(),
+            }
+        }).collect();
+        let variable: Vec<PostmanCollectionVariable> = path.iter().filter_map(|segment| segment.strip_prefix(':')).map(|name| PostmanCollectionVariable {
+            key: name.to_owned(),
+            value: String::new(),
+            description: None,
+            extensions: BTreeMap::new(),
+        }).collect();
+        PostmanCollectionUrl {
+            raw,
+            host,
+            path,
+            query: if !query.is_empty() { Some(query) } else { None },
+            variable: if !variable.is_empty() { Some(variable) } else { None },
+            extensions,
+        }
+    }
 }
 
 impl PostmanCollectionUrl {
     pub fn path_extract(&self) -> (String, Vec<PostmanCollectionVariable>) {
         let mut paths: Vec<String> = Vec::new();
         let empty_vec = Vec::new();
-        let thevars = if self.variable.is_some() {
-            (self.variable).as_ref().unwrap()
-        } else {
-            &empty_vec
+        let thevars = match &self.variable {
+            Some(variable) => variable,
+            None => &empty_vec,
         };
         let mut vars: Vec<PostmanCollectionVariable> = Vec::new();
         // for host in &self.host {
@@ -211,8 +527,8 @@ impl PostmanCollectionUrl {
         //     }
         // }
         for path in &self.path {
-            if path.starts_with(":") {
-                let variable = (&path[1..path.len()]).to_owned();
+            if let Some(variable) = path.strip_prefix(':') {
+                let variable = variable.to_owned();
                 for v in thevars {
                     if v.key == variable {
                         vars.push(v.clone());
@@ -228,18 +544,36 @@ impl PostmanCollectionUrl {
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostmanCollectionUrlQuery {
-    key: String,
-    value: serde_json::value::Value,
-}
-fn get_schema_to_string(schema: &OpenApi3Schema) -> String {
-    let example_value = match schema {
-        OpenApi3Schema::ArrayType(s) => {s.example.clone()},
-        OpenApi3Schema::ObjectType(s) => {s.example.clone()},
-        OpenApi3Schema::StringType(s) => {s.example.clone()},
-        OpenApi3Schema::IntegerType(s) => {s.example.clone()},
-        OpenApi3Schema::BooleanType(s) => {s.example.clone()},
-    };
-    get_value_to_string(example_value)
+    pub key: String,
+    pub value: serde_json::value::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<PostmanCollectionDescription>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+fn non_empty(text: &str) -> Option<String> {
+    if !text.is_empty() {
+        Some(text.to_owned())
+    } else {
+        None
+    }
+}
+/// The parameter description followed by the enum choices
+fn query_description(parameter: &OpenApi3Parameter) -> Option<String> {
+    let mut lines: Vec<String> = Vec::new();
+    if !parameter.description.is_empty() {
+        lines.push(parameter.description.clone());
+    }
+    if let Some(choices) = parameter.get_schema().and_then(|schema| schema.enum_values()) {
+        lines.push(format!("{}{}", ENUM_DESCRIPTION_PREFIX, choices.join(", ")));
+    }
+    if !lines.is_empty() {
+        Some(lines.join("\n\n"))
+    } else {
+        None
+    }
 }
 fn get_value_to_string(example_value: Option<Value>) -> String {
     match example_value {
@@ -250,28 +584,311 @@ fn get_value_to_string(example_value: Option<Value>) -> String {
         None => "".to_owned(),
     }
 }
+fn resolve_example<'a>(example: &'a OpenApi3ExampleOrRef, components: &'a Option<OpenApi3Components>, location: String) -> Result<&'a OpenApi3Example> {
+    example.resolve(components).ok_or_else(|| Error::InvalidReference {
+        pointer: location,
+        reference: match example {
+            OpenApi3ExampleOrRef::Ref(reference) => reference._ref.clone(),
+            OpenApi3ExampleOrRef::Example(_) => String::new(),
+        },
+    })
+}
+/// Fails on the first `$ref` of `parameters` that points nowhere
+fn check_parameter_refs(parameters: &[OpenApi3ParameterOrRef], components: &Option<OpenApi3Components>, location: &[&str], lenient: bool, warnings: &mut Vec<Warning>) -> Result<()> {
+    for (index, parameter) in parameters.iter().enumerate() {
+        if let OpenApi3ParameterOrRef::Ref(reference) = parameter {
+            if parameter.resolve(components).is_none() {
+                let err = Error::InvalidReference {
+                    pointer: format!("{}/parameters/{}", pointer(location), index),
+                    reference: reference._ref.clone(),
+                };
+                tolerate(err, WarningKind::Dropped, lenient, warnings)?;
+            }
+        }
+    }
+    Ok(())
+}
+/// One saved response per example of the documented responses, `location` is the pointer of the operation
+fn oa3_get_saved_responses(operation: &OpenApi3OperationMethod, components: &Option<OpenApi3Components>, location: &str, lenient: bool, warnings: &mut Vec<Warning>) -> Result<Vec<PostmanCollectionResponse>> {
+    let mut saved_responses: Vec<PostmanCollectionResponse> = Vec::new();
+    for (code, response) in &operation.responses {
+        for (media_type, content) in response.content.iter().flatten() {
+            let mut examples: Vec<(Option<String>, Option<Value>)> = Vec::new();
+            if content.example.is_some() {
+                examples.push((response.description.clone(), content.example.clone()));
+            }
+            for (name, example) in content.examples.iter().flatten() {
+                let location = format!("{}{}", location, pointer(&["responses", code, "content", media_type, "examples", name]));
+                match resolve_example(example, components, location) {
+                    Ok(example) => examples.push((Some(name.clone()), example.value.clone())),
+                    Err(err) => tolerate(err, WarningKind::Dropped, lenient, warnings)?,
+                }
+            }
+            for (name, value) in examples {
+                saved_responses.push(PostmanCollectionResponse {
+                    name,
+                    status: response.description.clone(),
+                    code: code.parse().ok(),
+                    body: get_value_to_string(value),
+                    extensions: BTreeMap::new(),
+                });
+            }
+        }
+    }
+    Ok(saved_responses)
+}
+/// Postman raw body language for a media type
+fn body_language(media_type: &str) -> &'static str {
+    if media_type.contains("json") {
+        "json"
+    } else if media_type.contains("xml") {
+        "xml"
+    } else if media_type.contains("html") {
+        "html"
+    } else if media_type.contains("javascript") {
+        "javascript"
+    } else {
+        "text"
+    }
+}
+/// Header name and placeholder value for each scheme of a security requirement, api key headers get a `{{name}}` variable
+fn security_headers(requirement: &BTreeMap<String, Vec<String>>, components: &Option<OpenApi3Components>) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for scheme_name in requirement.keys() {
+        let scheme = components.as_ref()
+            .and_then(|components| components.security_schemes.as_ref())
+            .and_then(|schemes| schemes.get(scheme_name));
+        let header = match scheme {
+            Some(scheme) if scheme._type == "apiKey" && scheme._in.as_deref() == Some("header") => scheme.name.clone()
+                .filter(|name| name.to_lowercase() != "authorization")
+                .map(|name| (name.clone(), format!("{{{{{}}}}}", name))),
+            _ => None,
+        };
+        let header = header.unwrap_or_else(|| ("Authorization".to_owned(), "Bearer token".to_owned()));
+        if !headers.iter().any(|(name, _)| name == &header.0) {
+            headers.push(header);
+        }
+    }
+    if headers.is_empty() {
+        headers.push(("Authorization".to_owned(), "Bearer token".to_owned()));
+    }
+    headers
+}
+
+fn resolve_schema_value(value: &Value, schemas: &BTreeMap<String, Value>, seen: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(obj) => {
+            if let Some(Value::String(reference)) = obj.get("$ref") {
+                let name = reference.trim_start_matches("#/components/schemas/").to_owned();
+                return match schemas.get(&name) {
+                    Some(target) if !seen.contains(&name) => {
+                        seen.push(name);
+                        let resolved = resolve_schema_value(target, schemas, seen);
+                        seen.pop();
+                        resolved
+                    },
+                    _ => Value::Object(serde_json::Map::new()),
+                };
+            }
+            Value::Object(obj.iter().map(|(k, v)| (k.clone(), resolve_schema_value(v, schemas, seen))).collect())
+        },
+        Value::Array(arr) => Value::Array(arr.iter().map(|v| resolve_schema_value(v, schemas, seen)).collect()),
+        _ => value.clone(),
+    }
+}
+/// The class (`2` for `2XX`) of a response key standing for a range of status codes
+fn status_class(code: &str) -> Option<u16> {
+    code.strip_suffix("XX")
+        .or_else(|| code.strip_suffix("xx"))
+        .and_then(|class| class.parse::<u16>().ok())
+        .filter(|class| (1..=5).contains(class))
+}
+fn oa3_get_test_event(operation: &OpenApi3OperationMethod, components: &Option<OpenApi3Components>) -> PostmanCollectionEvent {
+    let mut schemas: BTreeMap<String, Value> = BTreeMap::new();
+    if let Some(component_schemas) = components.as_ref().and_then(|c| c.schemas.as_ref()) {
+        for (name, schema) in component_schemas {
+            schemas.insert(name.clone(), serde_json::to_value(schema).unwrap_or(Value::Null));
+        }
+    }
+    let mut exec: Vec<String> = Vec::new();
+    let codes: Vec<u16> = operation.responses.keys().filter_map(|code| code.parse::<u16>().ok()).collect();
+    let classes: Vec<u16> = operation.responses.keys().filter_map(|code| status_class(code)).collect();
+    // `default` documents every other code, leaving nothing to assert on the status
+    if (!codes.is_empty() || !classes.is_empty()) && !operation.responses.contains_key("default") {
+        let code_list: Vec<String> = codes.iter().map(|code| code.to_string()).collect();
+        exec.push("pm.test(\"Status code is documented\", function () {".to_owned());
+        match (codes.as_slice(), classes.as_slice()) {
+            (_, []) => exec.push(format!("    pm.expect(pm.response.code).to.be.oneOf([{}]);", code_list.join(", "))),
+            ([], [class]) => exec.push(format!("    pm.expect(pm.response.code).to.be.within({}, {});", class * 100, class * 100 + 99)),
+            _ => {
+                let class_list: Vec<String> = classes.iter().map(|class| class.to_string()).collect();
+                exec.push(format!("    pm.expect(pm.response.code).to.satisfy(function (code) {{ return [{}].indexOf(code) !== -1 || [{}].indexOf(Math.floor(code / 100)) !== -1; }});", code_list.join(", "), class_list.join(", ")));
+            },
+        }
+        exec.push("});".to_owned());
+    }
+    for (code, response) in &operation.responses {
+        let condition = match (code.parse::<u16>(), status_class(code)) {
+            (Ok(code), _) => format!("pm.response.code === {}", code),
+            // the codes of the class documented on their own are checked against their response
+            (_, Some(class)) => {
+                let mut condition = format!("pm.response.code >= {} && pm.response.code <= {}", class * 100, class * 100 + 99);
+                for code in codes.iter().filter(|code| *code / 100 == class) {
+                    condition.push_str(&format!(" && pm.response.code !== {}", code));
+                }
+                condition
+            },
+            // `default` and keys that are no status code
+            _ => continue,
+        };
+        let contents = match &response.content {
+            Some(contents) if !contents.is_empty() => contents,
+            _ => continue,
+        };
+        exec.push(format!("if ({}) {{", condition));
+        let content_types: Vec<&String> = contents.keys().collect();
+        exec.push(format!("    pm.test(\"Content-Type is {}\", function () {{", content_types.iter().map(|c| c.as_str()).collect::<Vec<&str>>().join(" or ")));
+        exec.push("        var contentType = pm.response.headers.get(\"Content-Type\") || \"\";".to_owned());
+        exec.push(format!("        pm.expect({}.some(function (t) {{ return contentType.indexOf(t) === 0; }})).to.be.true;", serde_json::to_string(&content_types).unwrap_or_default()));
+        exec.push("    });".to_owned());
+        let json_schema = contents.iter()
+            .find(|(content_type, _)| content_type.contains("json"))
+            .and_then(|(_, content)| content.schema.as_ref());
+        if let Some(schema) = json_schema {
+            let value = resolve_schema_value(&serde_json::to_value(schema).unwrap_or(Value::Null), &schemas, &mut Vec::new());
+            exec.push("    pm.test(\"Response matches schema\", function () {".to_owned());
+            exec.push(format!("        pm.response.to.have.jsonSchema({});", value));
+            exec.push("    });".to_owned());
+        }
+        exec.push("}".to_owned());
+    }
+    PostmanCollectionEvent {
+        listen: "test".to_owned(),
+        script: PostmanCollectionScript {
+            _type: "text/javascript".to_owned(),
+            exec,
+            extensions: BTreeMap::new(),
+        },
+        extensions: BTreeMap::new(),
+    }
+}
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct PostmanConvertOption {
+    /// Host of every request url, usually a `{{HOST}}` variable
     pub host: String,
+    /// Path put before every request path
     pub preceding_path: String,
+    /// Text put before every folder name
     pub prepend_tag: String,
-    pub map_header: Vec<PostmanConvertOptionMapHeader>
+    /// Value used for every header named `key`
+    pub map_header: Vec<PostmanConvertOptionMapHeader>,
+    /// Emit a `test` event per request asserting the documented responses
+    #[serde(default)]
+    pub generate_tests: bool,
+    /// Embed the original document and operations so `to-openapi` can restore them unchanged
+    #[serde(default)]
+    pub round_trip: bool,
+    /// Turn unresolvable references and unsupported constructs into warnings instead of errors
+    #[serde(default)]
+    pub lenient: bool,
+    /// Write one collection per tag or per first path segment instead of a single one
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split: Option<PostmanSplit>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct PostmanConvertOptionMapHeader {
-    key: String,
-    value: String,
+    pub key: String,
+    pub value: String,
+}
+
+impl PostmanConvertOption {
+    /// Host of every request url, usually a `{{HOST}}` variable
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = host.to_owned();
+        self
+    }
+    pub fn with_preceding_path(mut self, preceding_path: &str) -> Self {
+        self.preceding_path = preceding_path.to_owned();
+        self
+    }
+    pub fn with_prepend_tag(mut self, prepend_tag: &str) -> Self {
+        self.prepend_tag = prepend_tag.to_owned();
+        self
+    }
+    /// Uses `value` for every header named `key`
+    pub fn with_map_header(mut self, key: &str, value: &str) -> Self {
+        self.map_header.push(PostmanConvertOptionMapHeader {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        });
+        self
+    }
+    pub fn with_generate_tests(mut self, generate_tests: bool) -> Self {
+        self.generate_tests = generate_tests;
+        self
+    }
+    pub fn with_round_trip(mut self, round_trip: bool) -> Self {
+        self.round_trip = round_trip;
+        self
+    }
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+    pub fn with_split(mut self, split: PostmanSplit) -> Self {
+        self.split = Some(split);
+        self
+    }
+}
+/// The items of the folder `path` below a tag folder, creating the missing folders with the scripts kept on the tag
+fn nested_items<'a>(folder: &'a mut PostmanCollectionItem, path: &[String], nested: &[OpenApi3PostmanFolder]) -> &'a mut Vec<PostmanCollectionItem> {
+    let mut items = folder.item.get_or_insert_with(Vec::new);
+    for depth in 0..path.len() {
+        let position = match items.iter().position(|item| item.item.is_some() && item.name == path[depth]) {
+            Some(position) => position,
+            None => {
+                let kept = nested.iter().find(|kept| kept.path[..] == path[..=depth]);
+                items.push(PostmanCollectionItem {
+                    name: path[depth].clone(),
+                    item: Some(Vec::new()),
+                    request: None,
+                    response: None,
+                    event: kept.and_then(|kept| kept.event.clone()),
+                    auth: None,
+                    extensions: kept.map(|kept| x_extensions(&kept.extensions)).unwrap_or_default(),
+                });
+                items.len() - 1
+            },
+        };
+        items = items[position].item.get_or_insert_with(Vec::new);
+    }
+    items
 }
-pub fn to_postman_colletion_2c1(openapi3: OpenApi3, postman_convert_option: PostmanConvertOption) -> PostmanCollection2c1 {
+/// Converts a document, also returning the warnings raised while converting
+pub fn to_postman_colletion_2c1(openapi3: OpenApi3, postman_convert_option: PostmanConvertOption) -> Result<(PostmanCollection2c1, Vec<Warning>)> {
+    let lenient = postman_convert_option.lenient;
+    let mut warnings: Vec<Warning> = Vec::new();
+    let mut extensions = x_extensions(&openapi3.extensions);
+    if postman_convert_option.round_trip {
+        let document = OpenApi3 {
+            paths: BTreeMap::new(),
+            ..openapi3.clone()
+        };
+        extensions.insert(ROUND_TRIP_DOCUMENT_KEY.to_owned(), serde_json::to_value(document).unwrap_or(Value::Null));
+    }
     let mut item_base: Vec<PostmanCollectionItem> = Vec::new();
     let mut postman_collection_item_map: BTreeMap<String, PostmanCollectionItem> = BTreeMap::new();
-    for (rawpath, operations) in &openapi3.paths {
+    for (rawpath, path_item) in &openapi3.paths {
         let path: String = format!("{}{}", postman_convert_option.preceding_path, rawpath);
-        for (method, operation) in operations {
-            let tag = if operation.tags.len() > 0 {
+        check_parameter_refs(&path_item.parameters, &openapi3.components, &["paths", rawpath], lenient, &mut warnings)?;
+        for (method, operation) in path_item.operations() {
+            check_parameter_refs(&operation.parameters, &openapi3.components, &["paths", rawpath, method], lenient, &mut warnings)?;
+            let tag = if !operation.tags.is_empty() {
                 operation.tags[0].clone()
             } else {
                 "_base".to_owned()
@@ -280,88 +897,174 @@ pub fn to_postman_colletion_2c1(openapi3: OpenApi3, postman_convert_option: Post
             let mut header_vec: Vec<PostmanCollectionHeader> = Vec::new();
             let mut query_vec: Vec<PostmanCollectionUrlQuery> = Vec::new();
             let mut variable_vec: Vec<PostmanCollectionVariable> = Vec::new();
+            let mut cookie_vec: Vec<String> = Vec::new();
             if let Some(secu) = &operation.security {
-                if secu.len() > 0 {
-                    let paramname: String = "Authorization".to_owned();
-                    let lc_name = paramname.clone().to_lowercase();
-                    let mut the_value: Option<String> = Some("Bearer token".to_owned());
-                    for kv in postman_convert_option.map_header.clone() {
-                        if kv.key.clone().to_lowercase() == lc_name {
-                            the_value = Some(kv.value);
-                            break;
+                if !secu.is_empty() {
+                    for scheme_name in secu[0].keys() {
+                        let scheme = openapi3.components.as_ref()
+                            .and_then(|components| components.security_schemes.as_ref())
+                            .and_then(|schemes| schemes.get(scheme_name));
+                        if let Some(scheme) = scheme {
+                            let is_header = scheme._type == "apiKey" && scheme._in.as_deref() == Some("header");
+                            let is_bearer = scheme._type == "http" && scheme.scheme.as_deref() == Some("bearer");
+                            let location = pointer(&["components", "securitySchemes", scheme_name]);
+                            if !is_header && !is_bearer && !warnings.iter().any(|w| w.pointer == location) {
+                                let message = format!("`{}` security scheme sent as an `Authorization: Bearer` header", scheme._type);
+                                warnings.push(Warning::new(WarningKind::Approximated, &location, message));
+                            }
                         }
                     }
-                    header_vec.push(PostmanCollectionHeader {
-                        key: paramname,
-                        _type: Some("text".into()),
-                        value: the_value.unwrap(),
-                    })
+                    for (paramname, default_value) in security_headers(&secu[0], &openapi3.components) {
+                        let lc_name = paramname.to_lowercase();
+                        let mut the_value: String = default_value;
+                        for kv in postman_convert_option.map_header.clone() {
+                            if kv.key.to_lowercase() == lc_name {
+                                the_value = kv.value;
+                                break;
+                            }
+                        }
+                        header_vec.push(PostmanCollectionHeader {
+                            key: paramname,
+                            _type: Some("text".into()),
+                            value: the_value,
+                            description: None,
+                            disabled: None,
+                            extensions: BTreeMap::new(),
+                        })
+                    }
                 }
             }
-            for parameter in &operation.parameters {
-                let example_value = get_schema_to_string(&parameter.schema);
+            for parameter in path_item.merged_parameters(operation, &openapi3.components) {
+                let example = parameter.example_value().unwrap_or(Value::Null);
                 if parameter._in == "query" {
-                    query_vec.push(PostmanCollectionUrlQuery {
-                        key: parameter.name.clone(),
-                        value: serde_json::value::Value::String(example_value),
-                    })
+                    for (key, value) in serialize_query(parameter, &example) {
+                        query_vec.push(PostmanCollectionUrlQuery {
+                            key,
+                            value: serde_json::value::Value::String(value),
+                            disabled: if parameter.required {
+                                None
+                            } else {
+                                Some(true)
+                            },
+                            description: query_description(parameter).map(Into::into),
+                            extensions: BTreeMap::new(),
+                        })
+                    }
                 } else if parameter._in == "header" {
-                    let lc_name: String = parameter.name.clone().to_lowercase();
-                    let mut the_value: Option<String> = Some(example_value);
+                    let lc_name: String = parameter.name.to_lowercase();
+                    let mut the_value: String = serialize_simple(&example, parameter.get_explode());
                     for kv in postman_convert_option.map_header.clone() {
-                        if kv.key.clone().to_lowercase() == lc_name {
-                            the_value = Some(kv.value);
+                        if kv.key.to_lowercase() == lc_name {
+                            the_value = kv.value;
                             break;
                         }
                     }
                     header_vec.push(PostmanCollectionHeader {
                         key: parameter.name.clone(),
                         _type: Some("text".into()),
-                        value: the_value.unwrap(),
+                        value: the_value,
+                        description: non_empty(&parameter.description).map(Into::into),
+                        disabled: None,
+                        extensions: BTreeMap::new(),
                     })
                 } else if parameter._in == "path" {
                     variable_vec.push(PostmanCollectionVariable {
                         key: parameter.name.clone(),
-                        value: example_value,
+                        value: serialize_path(parameter, &example),
+                        description: non_empty(&parameter.description).map(Into::into),
+                        extensions: BTreeMap::new(),
                     })
+                } else if parameter._in == "cookie" {
+                    cookie_vec.push(serialize_cookie(parameter, &example));
                 }
             }
-            let query = if query_vec.len() > 0 {
+            if !cookie_vec.is_empty() {
+                let mut the_value: String = cookie_vec.join("; ");
+                for kv in postman_convert_option.map_header.clone() {
+                    if kv.key.to_lowercase() == "cookie" {
+                        the_value = kv.value;
+                        break;
+                    }
+                }
+                header_vec.push(PostmanCollectionHeader {
+                    key: "Cookie".to_owned(),
+                    _type: Some("text".into()),
+                    value: the_value,
+                    description: None,
+                    disabled: None,
+                    extensions: BTreeMap::new(),
+                })
+            }
+            let query = if !query_vec.is_empty() {
                 Some(query_vec)
             } else {
                 None
             };
-            let body: Option<PostmanCollectionBody> = if operation.request_body.is_some() {
-                let raw: String = match operation.request_body.clone().unwrap().content {
-                    OpenApi3OperationMethodRequestBodyContent::ApplicationJson(a) => {
-                        get_value_to_string(a.example)
-                    },
-                };
-                Some(PostmanCollectionBody {
-                    mode: "raw".into(),
-                    raw: raw.into(),
-                    options: Some(PostmanCollectionBodyOptions {
-                        raw: PostmanCollectionBodyOptionsRaw {
-                            language: Some("json".into()),
-                        }
+            let media = operation.request_body.as_ref().and_then(|request_body| request_body.content.iter().next());
+            if let Some((media_type, _)) = media {
+                if media_type != "application/json" && !header_vec.iter().any(|h| h.key.to_lowercase() == "content-type") {
+                    header_vec.push(PostmanCollectionHeader {
+                        key: "Content-Type".to_owned(),
+                        _type: Some("text".into()),
+                        value: media_type.clone(),
+                        description: None,
+                        disabled: None,
+                        extensions: BTreeMap::new(),
                     })
-                })
-            } else {
-                None
-            };
-            let mut paths: Vec<String> = nonabsolute_path.clone().split('/').map(|s| s.to_owned()).collect();
-            for i in 0..paths.len() {
-                let the_path = paths[i].clone();
-                if paths[i].starts_with("{") {
-                    paths[i] = format!("{}{}", ':', the_path[1..(the_path.len() - 1)].to_owned());
                 }
             }
-            let variable = if variable_vec.len() > 0 {
+            // the `example` goes to the main request and each named example to a request variant
+            let mut body_examples: Vec<(Option<String>, Option<Value>)> = Vec::new();
+            if let Some((media_type, a)) = media {
+                if a.example.is_some() || a.examples.is_none() {
+                    body_examples.push((None, a.example.clone()));
+                }
+                for (name, example) in a.examples.iter().flatten() {
+                    let location = pointer(&["paths", rawpath, method, "requestBody", "content", media_type, "examples", name]);
+                    match resolve_example(example, &openapi3.components, location) {
+                        Ok(example) => body_examples.push((Some(name.clone()), example.value.clone())),
+                        Err(err) => tolerate(err, WarningKind::Dropped, lenient, &mut warnings)?,
+                    }
+                }
+                if body_language(media_type) == "text" && !media_type.starts_with("text/") {
+                    let location = pointer(&["paths", rawpath, method, "requestBody", "content", media_type]);
+                    warnings.push(Warning::new(WarningKind::Approximated, &location, format!("`{}` request body written as raw text", media_type)));
+                }
+            }
+            let make_body = |example: Option<Value>| media.map(|(media_type, _)| PostmanCollectionBody {
+                mode: "raw".into(),
+                raw: get_value_to_string(example),
+                formdata: None,
+                urlencoded: None,
+                graphql: None,
+                options: Some(PostmanCollectionBodyOptions {
+                    raw: PostmanCollectionBodyOptionsRaw {
+                        language: Some(body_language(media_type).into()),
+                        extensions: BTreeMap::new(),
+                    },
+                    extensions: BTreeMap::new(),
+                }),
+                extensions: BTreeMap::new(),
+            });
+            let body: Option<PostmanCollectionBody> = make_body(body_examples.first().and_then(|(_, value)| value.clone()));
+            let mut paths: Vec<String> = nonabsolute_path.split('/').map(|s| s.to_owned()).collect();
+            for the_path in paths.iter_mut() {
+                if the_path.len() > 2 && the_path.starts_with('{') && the_path.ends_with('}') {
+                    *the_path = format!("{}{}", ':', &the_path[1..(the_path.len() - 1)]);
+                } else if the_path.contains('{') || the_path.contains('}') {
+                    let err = Error::Unsupported {
+                        pointer: pointer(&["paths", rawpath]),
+                        message: format!("path template segment `{}`, only whole `{{name}}` segments are supported", the_path),
+                    };
+                    tolerate(err, WarningKind::Approximated, lenient, &mut warnings)?;
+                }
+            }
+            let variable = if !variable_vec.is_empty() {
                 Some(variable_vec)
             } else {
                 None
             };
-            let item_inner = PostmanCollectionItem {
+            let mut item_inner = PostmanCollectionItem {
                 name: path.clone(),
                 item: None,
                 request: Some(PostmanCollectionRequest {
@@ -370,45 +1073,96 @@ pub fn to_postman_colletion_2c1(openapi3: OpenApi3, postman_convert_option: Post
                         raw: format!("{}/{}", postman_convert_option.host.clone(), paths.join("/")),
                         host: vec![postman_convert_option.host.clone()],
                         path: paths,
-                        query: query,
-                        variable: variable,
+                        query,
+                        variable,
+                        extensions: BTreeMap::new(),
                     },
-                    body: body,
+                    body,
                     header: header_vec,
                     description: None,
-                    method: method.clone(),
+                    method: method.to_owned(),
+                    extensions: BTreeMap::new(),
                 }),
-                response: None,
-            };
-            match postman_collection_item_map.get_mut(&tag) {
-                Some(collection) => {
-                    if let Some(ref mut bv) = collection.item {
-                        bv.as_mut().push(item_inner);
+                response: {
+                    let saved_responses = oa3_get_saved_responses(operation, &openapi3.components, &pointer(&["paths", rawpath, method]), lenient, &mut warnings)?;
+                    if !saved_responses.is_empty() {
+                        Some(saved_responses)
+                    } else {
+                        None
                     }
                 },
-                None => {
-                    postman_collection_item_map.insert(
-                        tag.clone(),
-                        PostmanCollectionItem {
-                            name: format!("{}{}", postman_convert_option.prepend_tag.clone(), tag),
-                            item: Some(Box::new(vec![item_inner])),
-                            request: None,
-                            response: None,
-                        },
-                    );
-                }
+                event: {
+                    let mut events = operation.postman_events.clone().unwrap_or_default();
+                    let test_event = oa3_get_test_event(operation, &openapi3.components);
+                    // a collection converted back keeps the generated script among the operation events
+                    let generated = serde_json::to_value(&test_event).ok();
+                    if postman_convert_option.generate_tests && !events.iter().any(|event| serde_json::to_value(event).ok() == generated) {
+                        events.push(test_event);
+                    }
+                    if !events.is_empty() {
+                        Some(events)
+                    } else {
+                        None
+                    }
+                },
+                auth: None,
+                extensions: x_extensions(&operation.extensions),
             };
+            if let Some(operation_id) = &operation.operation_id {
+                item_inner.extensions.insert(OPERATION_ID_KEY.to_owned(), Value::String(operation_id.clone()));
+            }
+            let mut items: Vec<PostmanCollectionItem> = Vec::new();
+            for (name, example) in body_examples.iter().skip(1) {
+                let mut variant = PostmanCollectionItem {
+                    name: format!("{} ({})", path, name.clone().unwrap_or_default()),
+                    event: None,
+                    ..item_inner.clone()
+                };
+                if let Some(request) = &mut variant.request {
+                    request.body = make_body(example.clone());
+                }
+                items.push(variant);
+            }
+            if postman_convert_option.round_trip {
+                let round_trip = OpenApi3RoundTripOperation {
+                    path: rawpath.clone(),
+                    method: method.to_owned(),
+                    path_item: Some(path_item.without_operations()),
+                    operation: operation.clone(),
+                    request: item_inner.request.clone(),
+                    response: item_inner.response.clone(),
+                };
+                item_inner.extensions.insert(ROUND_TRIP_OPERATION_KEY.to_owned(), serde_json::to_value(round_trip).unwrap_or(Value::Null));
+            }
+            items.insert(0, item_inner);
+            let openapi3_tag = openapi3.tags.as_ref().and_then(|tags| tags.iter().find(|t| t.name == tag));
+            let folder = postman_collection_item_map.entry(tag.clone()).or_insert_with(|| PostmanCollectionItem {
+                name: format!("{}{}", postman_convert_option.prepend_tag.clone(), tag),
+                item: Some(Vec::new()),
+                request: None,
+                response: None,
+                event: openapi3_tag.and_then(|t| t.postman_events.clone()),
+                auth: None,
+                extensions: openapi3_tag.map(|t| x_extensions(&t.extensions)).unwrap_or_default(),
+            });
+            let nested = openapi3_tag.and_then(|t| t.postman_folders.as_deref()).unwrap_or_default();
+            nested_items(folder, operation.postman_folder.as_deref().unwrap_or_default(), nested).extend(items);
         }
     }
-    for (_tag, item) in &postman_collection_item_map {
+    for item in postman_collection_item_map.values() {
         item_base.push(item.clone());
     }
-    PostmanCollection2c1 {
+    Ok((PostmanCollection2c1 {
         info: PostmanCollectionInfo {
-            name: openapi3.info.title.into(),
+            name: openapi3.info.title,
             description: Some(openapi3.info.description.into()),
-            schema: "https://schema.getpostman.com/json/collection/v2.1.0/collection.json".into(),
+            schema: POSTMAN_SCHEMA_2C1.into(),
+            extensions: x_extensions(&openapi3.info.extensions),
         },
-        item: item_base.into()
-    }
+        item: item_base,
+        event: openapi3.postman_events,
+        auth: None,
+        variable: None,
+        extensions,
+    }, warnings))
 }
@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, BTreeSet};
+use schemars::JsonSchema;
+use serde_json::Value;
+use crate::error::{Result, Warning};
+use crate::merge::collect_variables;
+use crate::model::{OpenApi3, OpenApi3OperationMethod};
+use crate::postman_model::{to_postman_colletion_2c1, PostmanCollection2c1, PostmanConvertOption};
+
+/// How `split_to_postman_collections` groups the operations
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PostmanSplit {
+    /// One collection per first tag, the untagged operations going to `_base`
+    Tag,
+    /// One collection per first path segment, `/users/{id}` going to `users`
+    PathSegment,
+}
+
+fn group_name(split: PostmanSplit, path: &str, operation: &OpenApi3OperationMethod) -> String {
+    match split {
+        PostmanSplit::Tag => operation.tags.first().cloned().unwrap_or_else(|| "_base".to_owned()),
+        PostmanSplit::PathSegment => path.trim_start_matches('/').split('/').next()
+            .filter(|segment| !segment.is_empty())
+            .unwrap_or("_root")
+            .to_owned(),
+    }
+}
+
+/// The document split into one document per group, keeping everything but the paths
+pub fn split_openapi3(openapi: &OpenApi3, split: PostmanSplit) -> BTreeMap<String, OpenApi3> {
+    let mut groups: BTreeMap<String, OpenApi3> = BTreeMap::new();
+    for (path, path_item) in &openapi.paths {
+        for (method, operation) in path_item.operations() {
+            let group = groups.entry(group_name(split, path, operation)).or_insert_with(|| OpenApi3 {
+                paths: BTreeMap::new(),
+                ..openapi.clone()
+            });
+            let item = group.paths.entry(path.clone()).or_insert_with(|| path_item.without_operations());
+            if let Some(slot) = item.operation_mut(method) {
+                *slot = Some(operation.clone());
+            }
+        }
+    }
+    groups
+}
+
+/// Converts each group of `split_openapi3` into its own collection named `prependTag` + group. Split by tag,
+/// a collection holds a single folder, so its requests are put at the top level instead, and its scripts, auth
+/// and tag extensions go to the collection.
+pub fn split_to_postman_collections(openapi: OpenApi3, split: PostmanSplit, postman_convert_option: PostmanConvertOption) -> Result<(Vec<PostmanCollection2c1>, Vec<Warning>)> {
+    let mut collections: Vec<PostmanCollection2c1> = Vec::new();
+    let mut warnings: Vec<Warning> = Vec::new();
+    for (group, document) in split_openapi3(&openapi, split) {
+        let (mut collection, group_warnings) = to_postman_colletion_2c1(document, postman_convert_option.clone())?;
+        for warning in group_warnings {
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+        collection.info.name = format!("{}{}", postman_convert_option.prepend_tag, group);
+        if split == PostmanSplit::Tag && collection.item.len() == 1 {
+            let folder = collection.item.remove(0);
+            collection.item = folder.item.unwrap_or_default();
+            collection.event = collection.event.or(folder.event);
+            collection.auth = collection.auth.or(folder.auth);
+            for (key, value) in folder.extensions {
+                collection.info.extensions.entry(key).or_insert(value);
+            }
+        }
+        collections.push(collection);
+    }
+    Ok((collections, warnings))
+}
+
+/// A postman environment declaring the variables the collections use, with empty values
+pub fn postman_environment(name: &str, collections: &[PostmanCollection2c1]) -> Value {
+    let mut names: BTreeSet<String> = BTreeSet::new();
+    for collection in collections {
+        collect_variables(&serde_json::to_value(collection).unwrap_or(Value::Null), &mut names);
+    }
+    let values: Vec<Value> = names.into_iter().map(|name| serde_json::json!({
+        "key": name,
+        "value": "",
+        "type": "default",
+        "enabled": true
+    })).collect();
+    serde_json::json!({
+        "name": name,
+        "values": values,
+        "_postman_variable_scope": "environment"
+    })
+}
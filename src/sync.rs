@@ -0,0 +1,256 @@
+use serde_json::Value;
+use crate::error::{Result, Warning};
+use crate::model::{OpenApi3, OPERATION_ID_KEY, ROUND_TRIP_DOCUMENT_KEY, ROUND_TRIP_OPERATION_KEY};
+use crate::postman_model::{to_postman_colletion_2c1, PostmanCollection2c1, PostmanCollectionHeader, PostmanCollectionItem, PostmanCollectionRequest, PostmanCollectionUrlQuery, PostmanConvertOption};
+
+/// Folder the requests whose operation left the spec are moved to
+pub const REMOVED_FOLDER: &str = "_removed";
+
+/// Names of the requests `sync_postman_collection` touched
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncSummary {
+    pub updated: Vec<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Where a generated request goes: its tag folder and the nested folders below it, emptied of their items
+#[derive(Clone)]
+struct Placement {
+    folder: usize,
+    nested: Vec<PostmanCollectionItem>,
+}
+
+/// A generated request waiting for the existing request it updates
+struct Generated {
+    placement: Placement,
+    operation_id: Option<String>,
+    route: Option<String>,
+    item: Option<PostmanCollectionItem>,
+}
+
+/// operationId of the operation the request was generated from, read from the exported operation in
+/// collections older than `x-openapi-operation-id`
+fn operation_id(item: &PostmanCollectionItem) -> Option<String> {
+    item.extensions.get(OPERATION_ID_KEY)
+        .or_else(|| item.extensions.get(ROUND_TRIP_OPERATION_KEY).and_then(|operation| operation.pointer("/operation/operationId")))
+        .and_then(Value::as_str)
+        .map(str::to_owned)
+}
+
+/// The request as generated, kept in the exported operation when the collection was made with `--round-trip`
+fn exported_request(item: &PostmanCollectionItem) -> Option<PostmanCollectionRequest> {
+    item.extensions.get(ROUND_TRIP_OPERATION_KEY)
+        .and_then(|operation| operation.get("request"))
+        .and_then(|request| serde_json::from_value(request.clone()).ok())
+}
+
+/// `get /pets/{petId}`, the method and OpenAPI path of a request
+fn route(item: &PostmanCollectionItem) -> Option<String> {
+    item.request.as_ref().map(|request| format!("{} {}", request.method.to_lowercase(), request.url.path_extract().0))
+}
+
+/// The generated request for `existing`, by operationId first, then by method and path. Variants of one
+/// operation share a route and are paired in order.
+fn take_match(generated: &mut [Generated], existing: &PostmanCollectionItem) -> Option<(Placement, PostmanCollectionItem)> {
+    let operation_id = operation_id(existing);
+    let route = route(existing);
+    let position = generated.iter().position(|g| g.item.is_some() && operation_id.is_some() && g.operation_id == operation_id)
+        .or_else(|| generated.iter().position(|g| g.item.is_some() && route.is_some() && g.route == route))?;
+    generated[position].item.take().map(|item| (generated[position].placement.clone(), item))
+}
+
+/// The generated request carrying over what was authored by hand: the name, scripts, saved responses,
+/// auth, the values of the headers, query parameters and path variables that still exist, and the headers
+/// and query parameters added in postman. Entries the exported request shows were generated from the
+/// earlier spec are not carried over when the new spec no longer has them.
+fn update_item(existing: PostmanCollectionItem, generated: PostmanCollectionItem) -> PostmanCollectionItem {
+    let exported = exported_request(&existing);
+    let mut request = generated.request;
+    if let (Some(request), Some(old)) = (request.as_mut(), existing.request) {
+        for header in request.header.iter_mut() {
+            if let Some(old_header) = old.header.iter().find(|h| h.key.eq_ignore_ascii_case(&header.key)) {
+                header.value = old_header.value.clone();
+                header.disabled = old_header.disabled;
+            }
+        }
+        for query in request.url.query.iter_mut().flatten() {
+            if let Some(old_query) = old.url.query.iter().flatten().find(|q| q.key == query.key) {
+                query.value = old_query.value.clone();
+                query.disabled = old_query.disabled;
+            }
+        }
+        let added_headers: Vec<PostmanCollectionHeader> = old.header.iter()
+            .filter(|old_header| !request.header.iter().any(|h| h.key.eq_ignore_ascii_case(&old_header.key)))
+            .filter(|old_header| !exported.iter().flat_map(|e| e.header.iter()).any(|h| h.key.eq_ignore_ascii_case(&old_header.key)))
+            .cloned()
+            .collect();
+        request.header.extend(added_headers);
+        let added_queries: Vec<PostmanCollectionUrlQuery> = old.url.query.iter().flatten()
+            .filter(|old_query| !request.url.query.iter().flatten().any(|q| q.key == old_query.key))
+            .filter(|old_query| !exported.iter().flat_map(|e| e.url.query.iter().flatten()).any(|q| q.key == old_query.key))
+            .cloned()
+            .collect();
+        if !added_queries.is_empty() {
+            request.url.query.get_or_insert_with(Vec::new).extend(added_queries);
+        }
+        for variable in request.url.variable.iter_mut().flatten() {
+            if let Some(old_variable) = old.url.variable.iter().flatten().find(|v| v.key == variable.key) {
+                variable.value = old_variable.value.clone();
+            }
+        }
+        request.url.host = old.url.host;
+        request.url.raw = format!("{}/{}", request.url.host.join("."), request.url.path.join("/"));
+        if old.auth.is_some() {
+            request.auth = old.auth;
+        }
+        for (key, value) in old.extensions {
+            request.extensions.entry(key).or_insert(value);
+        }
+    }
+    let mut response = existing.response.unwrap_or_default();
+    for generated_response in generated.response.into_iter().flatten() {
+        if !response.iter().any(|r| r.name.is_some() && r.name == generated_response.name) {
+            response.push(generated_response);
+        }
+    }
+    let mut extensions = existing.extensions;
+    extensions.extend(generated.extensions);
+    PostmanCollectionItem {
+        name: existing.name,
+        item: None,
+        request,
+        response: if response.is_empty() { None } else { Some(response) },
+        event: existing.event.or(generated.event),
+        auth: existing.auth,
+        extensions,
+    }
+}
+
+/// Walks the existing items, updating the requests that match a generated one and taking out the others
+fn sync_items(items: Vec<PostmanCollectionItem>, generated: &mut [Generated], summary: &mut SyncSummary, removed: &mut Vec<PostmanCollectionItem>, restored: &mut Vec<(Placement, PostmanCollectionItem)>, in_removed: bool) -> Vec<PostmanCollectionItem> {
+    let mut kept: Vec<PostmanCollectionItem> = Vec::new();
+    for mut item in items {
+        if let Some(children) = item.item.take() {
+            let in_removed = in_removed || item.name == REMOVED_FOLDER;
+            item.item = Some(sync_items(children, generated, summary, removed, restored, in_removed));
+            kept.push(item);
+        } else if item.request.is_none() {
+            kept.push(item);
+        } else {
+            match take_match(generated, &item) {
+                Some((placement, generated_item)) => {
+                    summary.updated.push(item.name.clone());
+                    if in_removed {
+                        restored.push((placement, update_item(item, generated_item)));
+                    } else {
+                        kept.push(update_item(item, generated_item));
+                    }
+                },
+                None if in_removed => kept.push(item),
+                None => {
+                    summary.removed.push(item.name.clone());
+                    removed.push(item);
+                },
+            }
+        }
+    }
+    kept
+}
+
+/// Takes the requests out of the generated `items`, the folders they are in being added to `nested`
+fn flatten(items: Vec<PostmanCollectionItem>, folder: usize, nested: &[PostmanCollectionItem], generated: &mut Vec<Generated>) {
+    for mut item in items {
+        match item.item.take() {
+            Some(children) => {
+                let nested = [nested, &[item]].concat();
+                flatten(children, folder, &nested, generated);
+            },
+            None => generated.push(Generated {
+                placement: Placement {
+                    folder,
+                    nested: nested.to_vec(),
+                },
+                operation_id: operation_id(&item),
+                route: route(&item),
+                item: Some(item),
+            }),
+        }
+    }
+}
+
+/// The items of the folder named like `folder` in `items`, creating it from `folder` when missing. A new
+/// folder goes before `_removed`, which stays last.
+fn folder_items<'a>(items: &'a mut Vec<PostmanCollectionItem>, folder: &PostmanCollectionItem) -> &'a mut Vec<PostmanCollectionItem> {
+    let position = match items.iter().position(|existing| existing.item.is_some() && existing.name == folder.name) {
+        Some(position) => position,
+        None => {
+            let position = items.iter().position(|existing| existing.name == REMOVED_FOLDER).unwrap_or(items.len());
+            items.insert(position, PostmanCollectionItem {
+                item: Some(Vec::new()),
+                ..folder.clone()
+            });
+            position
+        },
+    };
+    items[position].item.get_or_insert_with(Vec::new)
+}
+
+/// Updates a collection built from an earlier version of the spec instead of generating it again. Matched
+/// requests get the new url, parameters and body and keep what was authored by hand, new operations are added
+/// to the folder they are generated in, nested folders included, and requests whose operation is gone move to
+/// the `_removed` folder. Requests under `_removed` that match again move back. Collection scripts, auth and
+/// variables are left untouched.
+pub fn sync_postman_collection(existing: PostmanCollection2c1, openapi: OpenApi3, postman_convert_option: PostmanConvertOption) -> Result<(PostmanCollection2c1, SyncSummary, Vec<Warning>)> {
+    let (fresh, warnings) = to_postman_colletion_2c1(openapi, postman_convert_option)?;
+    let mut folders: Vec<PostmanCollectionItem> = Vec::new();
+    let mut generated: Vec<Generated> = Vec::new();
+    for (index, mut folder) in fresh.item.into_iter().enumerate() {
+        flatten(folder.item.take().unwrap_or_default(), index, &[], &mut generated);
+        folders.push(folder);
+    }
+
+    let mut summary = SyncSummary::default();
+    let mut removed: Vec<PostmanCollectionItem> = Vec::new();
+    let mut restored: Vec<(Placement, PostmanCollectionItem)> = Vec::new();
+    let mut items = sync_items(existing.item, &mut generated, &mut summary, &mut removed, &mut restored, false);
+    let added: Vec<(Placement, PostmanCollectionItem)> = generated.into_iter()
+        .filter_map(|Generated { placement, item, .. }| item.map(|item| (placement, item)))
+        .collect();
+    summary.added = added.iter().map(|(_, item)| item.name.clone()).collect();
+    for (placement, item) in restored.into_iter().chain(added) {
+        let mut target = folder_items(&mut items, &folders[placement.folder]);
+        for folder in &placement.nested {
+            target = folder_items(target, folder);
+        }
+        target.push(item);
+    }
+    items.retain(|item| item.name != REMOVED_FOLDER || item.item.as_ref().is_some_and(|children| !children.is_empty()));
+    if !removed.is_empty() {
+        match items.iter_mut().find(|item| item.item.is_some() && item.name == REMOVED_FOLDER) {
+            Some(folder) => folder.item.get_or_insert_with(Vec::new).extend(removed),
+            None => items.push(PostmanCollectionItem {
+                name: REMOVED_FOLDER.to_owned(),
+                item: Some(removed),
+                request: None,
+                response: None,
+                event: None,
+                auth: None,
+                extensions: Default::default(),
+            }),
+        }
+    }
+
+    let mut extensions = existing.extensions;
+    if let Some(document) = fresh.extensions.get(ROUND_TRIP_DOCUMENT_KEY) {
+        extensions.insert(ROUND_TRIP_DOCUMENT_KEY.to_owned(), document.clone());
+    }
+    Ok((PostmanCollection2c1 {
+        info: existing.info,
+        item: items,
+        event: existing.event,
+        auth: existing.auth,
+        variable: existing.variable,
+        extensions,
+    }, summary, warnings))
+}
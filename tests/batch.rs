@@ -0,0 +1,53 @@
+use std::path::Path;
+
+mod common;
+use common::{fixture, output, temp_dir};
+
+fn copy(fixture_path: &str, to: &Path) {
+    std::fs::create_dir_all(to.parent().unwrap()).unwrap();
+    std::fs::copy(fixture(fixture_path), to).unwrap();
+}
+
+#[test]
+fn converts_a_directory_into_a_mirrored_tree() {
+    let dir = temp_dir("batch");
+    let services = dir.join("services");
+    copy("round_trip/petstore.json", &services.join("pets/openapi.json"));
+    copy("config/typo.json", &services.join("pets/openapi.config.json"));
+    copy("round_trip/petstore.json", &services.join("store/petstore.json"));
+    copy("merge.postman.json", &services.join("users/users.postman.json"));
+    copy("invalid/missing_responses.json", &services.join("broken.json"));
+    std::fs::write(services.join("package.json"), "{\"name\": \"services\"}").unwrap();
+    std::fs::write(services.join("truncated.json"), "{\"openapi\": \"3.0.0\", broken").unwrap();
+    let converted = dir.join("out");
+
+    let result = output(&["batch", services.to_str().unwrap(), "-o", converted.to_str().unwrap()]);
+    let stdout = String::from_utf8(result.stdout).unwrap();
+    assert_eq!(result.status.code(), Some(1), "{}", stdout);
+    assert!(converted.join("users/users.openapi.json").is_file());
+    assert!(converted.join("store/petstore.postman.json").is_file());
+    assert!(!converted.join("broken.postman.json").exists());
+    let row = |name: &str| stdout.lines().find(|line| line.contains(name)).unwrap_or_else(|| panic!("no row for {} in {}", name, stdout)).to_owned();
+    assert!(row("users.postman.json").contains("to-openapi"), "{}", stdout);
+    assert!(row("users.postman.json").ends_with("ok"), "{}", stdout);
+    assert!(row("petstore.json").contains("to-postman"), "{}", stdout);
+    assert!(row("package.json").ends_with("skipped"), "{}", stdout);
+    assert!(row("broken.json").contains("failed: cannot parse"), "{}", stdout);
+    assert!(row("truncated.json").contains("failed: cannot parse"), "{}", stdout);
+    // the config next to the spec is used, and its typo fails that spec only
+    assert!(row("openapi.json").contains("did you mean `precedingPath`?"), "{}", stdout);
+    assert!(stdout.ends_with("2 converted, 1 with warnings, 1 skipped, 3 failed\n"), "{}", stdout);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn writes_a_single_file_into_the_output_directory() {
+    let dir = temp_dir("batch_single");
+    copy("round_trip/petstore.json", &dir.join("in/pets.json"));
+    let converted = dir.join("out");
+
+    let result = output(&["batch", "-o", converted.to_str().unwrap(), dir.join("in/pets.json").to_str().unwrap()]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stdout));
+    assert!(converted.join("pets.postman.json").is_file());
+    std::fs::remove_dir_all(dir).unwrap();
+}
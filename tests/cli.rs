@@ -0,0 +1,129 @@
+use std::io::Write;
+use std::process::Stdio;
+use serde_json::Value;
+
+mod common;
+use common::{converter, fixture, output, read_json, run, temp_file};
+
+#[test]
+fn convert_detects_the_direction() {
+    let collection = temp_file("cli_convert_collection");
+    let spec = temp_file("cli_convert_spec");
+    run(&["convert", "-o", collection.to_str().unwrap(), fixture("round_trip/petstore.json").to_str().unwrap()]);
+    let converted = read_json(&collection);
+    assert!(converted["info"]["schema"].as_str().unwrap().contains("v2.1.0"));
+
+    run(&["convert", "-o", spec.to_str().unwrap(), fixture("v1.postman.json").to_str().unwrap()]);
+    let converted = read_json(&spec);
+    assert_eq!(converted["openapi"], "3.0.0");
+    std::fs::remove_file(collection).unwrap();
+    std::fs::remove_file(spec).unwrap();
+}
+
+#[test]
+fn validate_reports_the_kind_of_input() {
+    let stdout = String::from_utf8(run(&["validate", fixture("merge.postman.json").to_str().unwrap()]).stdout).unwrap();
+    assert!(stdout.contains("valid postman collection"), "{}", stdout);
+
+    let result = output(&["validate", fixture("invalid/missing_responses.json").to_str().unwrap()]);
+    assert_eq!(result.status.code(), Some(1));
+}
+
+#[test]
+fn diff_lists_changed_operations() {
+    let mut spec = read_json(&fixture("round_trip/petstore.json"));
+    let paths = spec["paths"].as_object_mut().unwrap();
+    paths["/pets/{petId}"]["get"]["summary"] = Value::String("Fetch a pet".to_owned());
+    paths["/pets"]["get"]["parameters"][0]["required"] = Value::Bool(false);
+    paths["/pets"]["get"]["responses"]["404"] = serde_json::json!({ "description": "Not found" });
+    let changed = temp_file("cli_diff");
+    std::fs::write(&changed, spec.to_string()).unwrap();
+
+    let stdout = String::from_utf8(run(&["diff", fixture("round_trip/petstore.json").to_str().unwrap(), changed.to_str().unwrap()]).stdout).unwrap();
+    assert_eq!(stdout, "+ GET /pets: response 404 added\n~ GET /pets/{petId}\n2 change(s), 0 breaking\n");
+    std::fs::remove_file(changed).unwrap();
+}
+
+#[test]
+fn diff_fails_on_breaking_changes() {
+    let mut spec = read_json(&fixture("round_trip/petstore.json"));
+    spec["paths"]["/pets"].as_object_mut().unwrap().remove("post");
+    spec["paths"]["/pets"]["get"]["parameters"][0]["required"] = Value::Bool(true);
+    spec["paths"]["/pets"]["get"]["security"] = serde_json::json!([{ "bearer": [] }]);
+    let pet = &mut spec["components"]["schemas"]["Pet"]["properties"];
+    pet.as_object_mut().unwrap().remove("tag");
+    pet["id"] = serde_json::json!({ "type": "string" });
+    let changed = temp_file("diff_breaking");
+    std::fs::write(&changed, spec.to_string()).unwrap();
+
+    let result = output(&["diff", "--format", "json", fixture("round_trip/petstore.json").to_str().unwrap(), changed.to_str().unwrap()]);
+    std::fs::remove_file(changed).unwrap();
+    assert_eq!(result.status.code(), Some(2));
+    let changes: Vec<Value> = serde_json::from_slice(&result.stdout).unwrap();
+    let breaking: Vec<String> = changes.iter()
+        .filter(|change| change["breaking"] == true)
+        .map(|change| format!("{} {} {}", change["method"].as_str().unwrap(), change["path"].as_str().unwrap(), change["detail"].as_str().unwrap()))
+        .collect();
+    assert_eq!(breaking, vec![
+        "get /pets query parameter `limit` became required",
+        "get /pets response 200 application/json[]: property `id`: type changed from integer to string",
+        "get /pets response 200 application/json[]: property `tag` removed",
+        "get /pets security `apiKey` removed",
+        "post /pets ",
+        "get /pets/{petId} response 200 application/json: property `id`: type changed from integer to string",
+        "get /pets/{petId} response 200 application/json: property `tag` removed",
+    ]);
+}
+
+#[test]
+fn pipes_stdin_to_stdout() {
+    let mut child = converter(&["convert", "-", "-o", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run converter");
+    let spec = std::fs::read(fixture("round_trip/petstore.json")).unwrap();
+    child.stdin.take().unwrap().write_all(&spec).unwrap();
+    let result = child.wait_with_output().unwrap();
+    assert!(result.status.success());
+    let collection: Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(collection["info"]["name"], "Petstore");
+}
+
+#[test]
+fn flags_and_environment_override_the_config() {
+    let output = converter(&["to-postman", "-c", fixture("round-trip-config.json").to_str().unwrap(), "--host", "{{BASE_URL}}", "--map-header", "X-Tenant=acme"])
+        .arg(fixture("round_trip/petstore.json"))
+        .env("OPENAPI3_X_POSTMAN_PRECEDING_PATH", "/v2")
+        .env("OPENAPI3_X_POSTMAN_HOST", "ignored")
+        .output()
+        .expect("failed to run converter");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let collection: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let folder = &collection["item"][0];
+    assert!(folder["name"].as_str().unwrap().starts_with("Service "));
+    let url = &folder["item"][0]["request"]["url"];
+    assert_eq!(url["host"][0], "{{BASE_URL}}");
+    assert_eq!(url["path"][0], "v2");
+}
+
+#[test]
+fn environment_raises_flags_and_keeps_commas_in_header_values() {
+    let output = converter(&["to-postman"])
+        .arg(fixture("round_trip/petstore.json"))
+        .env("OPENAPI3_X_POSTMAN_GENERATE_TESTS", "true")
+        .env("OPENAPI3_X_POSTMAN_MAP_HEADER", "X-Request-Id=a, b\nX-Other=c")
+        .output()
+        .expect("failed to run converter");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let collection: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let requests: Vec<&Value> = collection["item"].as_array().unwrap().iter()
+        .flat_map(|folder| folder["item"].as_array().unwrap())
+        .collect();
+    assert!(requests.iter().all(|request| request["event"][0]["listen"] == "test"));
+    let header = requests.iter()
+        .flat_map(|request| request["request"]["header"].as_array().unwrap())
+        .find(|header| header["key"] == "X-Request-Id")
+        .unwrap();
+    assert_eq!(header["value"], "a, b");
+}
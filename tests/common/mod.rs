@@ -0,0 +1,43 @@
+//! Helpers shared by the integration tests, which run the built converter on the fixtures
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use serde_json::Value;
+
+pub fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(path)
+}
+
+/// A json file in the temp directory, `name` keeping apart the tests running in parallel
+pub fn temp_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("openapi3_x_postman_{}_{}.json", name, std::process::id()))
+}
+
+/// A directory in the temp directory, see `temp_file`
+pub fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("openapi3_x_postman_{}_{}", name, std::process::id()))
+}
+
+/// The converter called with `args`, for the tests adding arguments, environment variables or stdin
+pub fn converter(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_openapi3_x_postman"));
+    command.args(args);
+    command
+}
+
+/// Runs the converter whatever its exit status
+pub fn output(args: &[&str]) -> Output {
+    converter(args).output().expect("failed to run converter")
+}
+
+/// Runs the converter, failing the test when it fails
+pub fn run(args: &[&str]) -> Output {
+    let result = output(args);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    result
+}
+
+pub fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
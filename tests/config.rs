@@ -0,0 +1,35 @@
+use std::path::Path;
+use serde_json::Value;
+
+mod common;
+use common::{fixture, output, run};
+
+#[test]
+fn layers_a_toml_config_on_a_yaml_base() {
+    let output = run(&["to-postman", "-c", fixture("config/service.toml").to_str().unwrap(), fixture("round_trip/petstore.json").to_str().unwrap()]);
+    let collection: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let folder = &collection["item"][0];
+    assert!(folder["name"].as_str().unwrap().starts_with("Platform "));
+    let url = &folder["item"][0]["request"]["url"];
+    assert_eq!(url["host"][0], "{{HOST}}");
+    assert_eq!(url["path"][0], "pets");
+    assert_eq!(url["path"][1], "api");
+}
+
+#[test]
+fn rejects_unknown_keys() {
+    let output = output(&["to-postman", "-c", fixture("config/typo.json").to_str().unwrap(), fixture("round_trip/petstore.json").to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("unknown field `precedingpath`"), "{}", stderr);
+    assert!(stderr.contains("did you mean `precedingPath`?"), "{}", stderr);
+}
+
+#[test]
+fn published_schemas_are_up_to_date() {
+    for direction in &["to-postman", "to-openapi", "merge-specs"] {
+        let output = run(&["config-schema", direction]);
+        let published = std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("schema").join(format!("{}-config.schema.json", direction))).unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), published, "regenerate schema/{}-config.schema.json", direction);
+    }
+}
@@ -0,0 +1,41 @@
+mod common;
+use common::{fixture, output, temp_file};
+
+/// Runs the converter with `flag` on a fixture that must fail, returning stderr
+fn error(flag: &str, input: &str) -> String {
+    let converted = temp_file("errors");
+    let result = output(&[flag, "-o", converted.to_str().unwrap(), fixture(input).to_str().unwrap()]);
+    assert_eq!(result.status.code(), Some(1));
+    assert!(!converted.exists());
+    String::from_utf8(result.stderr).unwrap()
+}
+
+#[test]
+fn reports_parse_errors_with_a_json_pointer() {
+    let stderr = error("to-postman", "invalid/missing_responses.json");
+    assert!(stderr.contains("missing_responses.json#/paths/~1users/get: missing field `responses`"), "{}", stderr);
+}
+
+#[test]
+fn reports_unresolved_references() {
+    let stderr = error("to-postman", "invalid/unknown_parameter_ref.json");
+    assert!(stderr.contains("cannot resolve #/components/parameters/page at /paths/~1users/get/parameters/0"), "{}", stderr);
+}
+
+#[test]
+fn reports_missing_input_files() {
+    let stderr = error("to-postman", "invalid/does_not_exist.json");
+    assert!(stderr.starts_with("error: cannot read or write"), "{}", stderr);
+}
+
+#[test]
+fn reports_v1_folder_cycles() {
+    let stderr = error("to-openapi", "invalid/v1_folder_cycle.postman.json");
+    assert!(stderr.contains("v1_folder_cycle.postman.json#/folders/0: folder `f1` is listed more than once in `folders_order`"), "{}", stderr);
+}
+
+#[test]
+fn reports_v1_binary_bodies() {
+    let stderr = error("to-openapi", "invalid/v1_binary_body.postman.json");
+    assert!(stderr.contains("unsupported `file` request body at /item/0/request/body/mode"), "{}", stderr);
+}
@@ -0,0 +1,31 @@
+use serde_json::Value;
+
+mod common;
+use common::{fixture, output, read_json, run, temp_file};
+
+#[test]
+fn fails_without_lenient() {
+    let collection = temp_file("lenient_strict");
+    let result = output(&["to-postman", "-o", collection.to_str().unwrap(), fixture("invalid/lenient.json").to_str().unwrap()]);
+    assert_eq!(result.status.code(), Some(1));
+}
+
+#[test]
+fn converts_what_it_can_and_reports_the_rest() {
+    let output = temp_file("lenient_collection");
+    let report = temp_file("lenient_report");
+    run(&["to-postman", "--lenient", "--warnings-report", report.to_str().unwrap(), "-o", output.to_str().unwrap(), fixture("invalid/lenient.json").to_str().unwrap()]);
+
+    let collection = read_json(&output);
+    let methods: Vec<&Value> = collection["item"][0]["item"].as_array().unwrap().iter()
+        .map(|request| &request["request"]["method"])
+        .collect();
+    assert_eq!(methods, vec!["get", "put"]);
+
+    let warnings: Vec<Value> = serde_json::from_str(&std::fs::read_to_string(&report).unwrap()).unwrap();
+    let find = |pointer: &str| warnings.iter().find(|warning| warning["pointer"] == pointer).unwrap_or_else(|| panic!("no warning at {} in {:?}", pointer, warnings));
+    assert_eq!(find("/paths/~1users/delete")["kind"], "dropped");
+    assert_eq!(find("/paths/~1users/get/parameters/0")["kind"], "dropped");
+    std::fs::remove_file(output).unwrap();
+    std::fs::remove_file(report).unwrap();
+}
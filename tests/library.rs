@@ -0,0 +1,35 @@
+use std::path::Path;
+use openapi3_x_postman::{read_openapi3, to_openapi3, to_postman_colletion_2c1, OpenApi3ConvertOption, PostmanConvertOption};
+
+#[test]
+fn converts_through_the_library_api() {
+    let spec = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/round_trip/petstore.json");
+    let openapi = read_openapi3(&spec).unwrap();
+    let option = PostmanConvertOption::default()
+        .with_host("{{HOST}}")
+        .with_preceding_path("/api")
+        .with_map_header("Authorization", "Bearer {{token}}");
+    let (collection, _) = to_postman_colletion_2c1(openapi, option).unwrap();
+    let request = collection.item[0].item.as_ref().unwrap()[0].request.as_ref().unwrap();
+    assert_eq!(request.url.host, vec!["{{HOST}}"]);
+    assert_eq!(request.url.path[0], "api");
+
+    let option = OpenApi3ConvertOption::default()
+        .with_server("https://api.example.com", "production")
+        .with_mapping("{{HOST}}", "https://api.example.com");
+    let (restored, warnings) = to_openapi3(collection, option).unwrap();
+    assert_eq!(restored.servers[0].url, "https://api.example.com");
+    assert!(restored.paths.keys().all(|path| path.starts_with("/api/")));
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn errors_chain_into_std_errors() {
+    fn read(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        read_openapi3(path)?;
+        Ok(())
+    }
+    let err = read(Path::new("tests/fixtures/invalid/does_not_exist.json")).unwrap_err();
+    let cause = err.source().and_then(|source| source.downcast_ref::<std::io::Error>()).unwrap();
+    assert_eq!(cause.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,68 @@
+mod common;
+use std::io::Write;
+use std::process::Stdio;
+use serde_json::Value;
+use common::{converter, fixture, read_json, run, temp_file};
+
+fn merged() -> Vec<u8> {
+    run(&["merge-specs", fixture("merge/platform.yaml").to_str().unwrap()]).stdout
+}
+
+#[test]
+fn merges_specs_into_a_folder_per_service() {
+    let collection: Value = serde_json::from_slice(&merged()).unwrap();
+    assert_eq!(collection["info"]["name"], "Platform");
+    let folders: Vec<&str> = collection["item"].as_array().unwrap().iter().map(|folder| folder["name"].as_str().unwrap()).collect();
+    assert_eq!(folders, vec!["Users", "Order service"]);
+    let orders = &collection["item"][1]["item"][0]["item"][0]["request"];
+    assert_eq!(orders["url"]["raw"], "{{HOST}}/orders-api/orders");
+
+    // every service sends the same bearer token, so it is set once on the collection
+    assert_eq!(collection["auth"]["bearer"][0]["value"], "{{token}}");
+    assert!(orders["header"].as_array().unwrap().iter().all(|header| header["key"] != "Authorization"));
+    let health = &collection["item"][0]["item"][0]["item"][0]["request"];
+    assert_eq!(health["auth"]["type"], "noauth");
+
+    let variables: Vec<(&str, &str)> = collection["variable"].as_array().unwrap().iter()
+        .map(|variable| (variable["key"].as_str().unwrap(), variable["value"].as_str().unwrap()))
+        .collect();
+    assert_eq!(variables, vec![("HOST", "https://api.example.com"), ("tenant", ""), ("token", "")]);
+}
+
+#[test]
+fn requests_inherit_the_collection_auth() {
+    let mut child = converter(&["to-openapi"]).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(&merged()).unwrap();
+    let result = child.wait_with_output().unwrap();
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    let openapi: Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(openapi["paths"]["/orders-api/orders"]["post"]["security"], serde_json::json!([{ "bearer": [] }]));
+    assert_eq!(openapi["paths"]["/users-api/health"]["get"]["security"], serde_json::json!([{}]));
+}
+
+#[test]
+fn merges_collections_and_reports_conflicts() {
+    let report = temp_file("merge_report");
+    // named like the first collection, the conflict is still told apart by input
+    let qa = temp_file("merge_qa");
+    let mut collection = read_json(&fixture("merge/qa.postman.json"));
+    collection["info"]["name"] = Value::from("Merge");
+    std::fs::write(&qa, collection.to_string()).unwrap();
+    let merge = fixture("merge.postman.json");
+    let result = run(&["merge-collections", merge.to_str().unwrap(), qa.to_str().unwrap(), "--title", "Platform", "--warnings-report", report.to_str().unwrap()]);
+    let spec: Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(spec["info"]["title"], "Platform");
+    assert!(spec["paths"]["/orders"]["get"].is_object());
+    let responses = spec["paths"]["/users"]["post"]["responses"].as_object().unwrap();
+    assert!(responses.contains_key("201") && responses.contains_key("400"));
+    let tags: Vec<&str> = spec["tags"].as_array().unwrap().iter().map(|tag| tag["name"].as_str().unwrap()).collect();
+    assert_eq!(tags, vec!["Users", "Admin", "Orders"]);
+
+    let warnings: Vec<Value> = serde_json::from_value(read_json(&report)).unwrap();
+    let conflicts: Vec<&Value> = warnings.iter().filter(|warning| warning["kind"] == "conflict").collect();
+    assert_eq!(conflicts.len(), 1, "{:?}", warnings);
+    assert_eq!(conflicts[0]["message"], format!("POST /users is defined by several collections, merged: {}, {}", merge.display(), qa.display()));
+    assert!(warnings.iter().all(|warning| warning["source"] == merge.to_str().unwrap() || warning["source"] == qa.to_str().unwrap()), "{:?}", warnings);
+    std::fs::remove_file(report).unwrap();
+    std::fs::remove_file(qa).unwrap();
+}
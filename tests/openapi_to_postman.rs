@@ -0,0 +1,93 @@
+use serde_json::Value;
+
+mod common;
+use common::{fixture, read_json, run, temp_dir, temp_file};
+
+fn stp(spec: &str, args: &[&str]) -> Value {
+    let output = temp_file(&format!("stp_{}", spec.replace('/', "_")));
+    run(&[&["to-postman", "-o", output.to_str().unwrap(), fixture(spec).to_str().unwrap()], args].concat());
+    let collection = read_json(&output);
+    std::fs::remove_file(&output).unwrap();
+    collection
+}
+
+#[test]
+fn generates_request_variants_and_saved_responses_from_examples() {
+    let collection = stp("round_trip/examples.json", &[]);
+    let items = collection["item"][0]["item"].as_array().unwrap();
+    let names: Vec<&str> = items.iter().map(|item| item["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["/users", "/users (guest)"]);
+    assert_eq!(items[0]["request"]["body"]["raw"], "{\"admin\":true,\"name\":\"root\"}");
+    assert_eq!(items[1]["request"]["body"]["raw"], "{\"name\":\"guest\"}");
+    let responses = items[0]["response"].as_array().unwrap();
+    let saved: Vec<(&str, &Value)> = responses.iter().map(|r| (r["name"].as_str().unwrap(), &r["code"])).collect();
+    assert_eq!(saved, vec![("admin", &Value::from(201)), ("guest", &Value::from(201)), ("Conflict", &Value::from(409))]);
+}
+
+#[test]
+fn writes_postman_v2_0_collections() {
+    let collection = stp("round_trip/petstore.json", &["--postman-version", "2.0"]);
+    assert_eq!(collection["info"]["schema"], "https://schema.getpostman.com/json/collection/v2.0.0/collection.json");
+    assert!(!collection["item"].as_array().unwrap().is_empty());
+}
+
+fn test_script(item: &Value) -> Vec<&str> {
+    let event = &item["event"][0];
+    assert_eq!(event["listen"], "test");
+    event["script"]["exec"].as_array().unwrap().iter().map(|line| line.as_str().unwrap()).collect()
+}
+
+#[test]
+fn generates_test_scripts_from_composed_schemas() {
+    let collection = stp("composed.json", &["-c", fixture("generate-tests-config.json").to_str().unwrap()]);
+    let exec = test_script(&collection["item"][0]["item"][0]);
+    assert_eq!(exec[1], "    pm.expect(pm.response.code).to.be.oneOf([200, 404]);");
+    assert!(exec.contains(&"if (pm.response.code === 200) {"));
+    let schema_line = exec.iter().find(|line| line.contains("jsonSchema")).unwrap();
+    let schema: Value = serde_json::from_str(schema_line.trim().trim_start_matches("pm.response.to.have.jsonSchema(").trim_end_matches(");")).unwrap();
+    // the `$ref` inside `allOf` is resolved against the components
+    assert_eq!(schema["allOf"][0]["properties"]["name"]["type"], "string");
+    assert_eq!(schema["allOf"][1]["properties"]["lives"]["type"], "integer");
+}
+
+#[test]
+fn generates_test_scripts_for_status_code_ranges() {
+    let collection = stp("status_ranges.json", &["-c", fixture("generate-tests-config.json").to_str().unwrap()]);
+    let list = test_script(&collection["item"][0]["item"][0]);
+    assert_eq!(list[1], "    pm.expect(pm.response.code).to.be.within(200, 299);");
+    assert!(list.contains(&"if (pm.response.code >= 200 && pm.response.code <= 299) {"), "{:?}", list);
+
+    // `default` allows any status and gets no checks of its own
+    let create = test_script(&collection["item"][0]["item"][1]);
+    assert!(!create.iter().any(|line| line.contains("Status code is documented")), "{:?}", create);
+    let conditions: Vec<&&str> = create.iter().filter(|line| line.starts_with("if (")).collect();
+    assert_eq!(conditions, vec![&"if (pm.response.code === 201) {", &"if (pm.response.code >= 200 && pm.response.code <= 299 && pm.response.code !== 201) {"]);
+}
+
+#[test]
+fn splits_into_a_collection_per_tag_with_an_environment() {
+    let dir = temp_dir("split");
+    let environment = dir.join("environment.json");
+    run(&["to-postman", "--split", "tag", "--prepend-tag", "Users ", "--host", "{{HOST}}", "-o", dir.join("collections").to_str().unwrap(), "--environment", environment.to_str().unwrap(), fixture("merge/users.json").to_str().unwrap()]);
+    let health = read_json(&dir.join("collections/Users_health.postman_collection.json"));
+    assert_eq!(health["info"]["name"], "Users health");
+    assert_eq!(health["item"][0]["name"], "/health");
+    let users = read_json(&dir.join("collections/Users_users.postman_collection.json"));
+    assert_eq!(users["item"][0]["request"]["url"]["raw"], "{{HOST}}/users");
+    let environment = read_json(&environment);
+    assert_eq!(environment["values"][0]["key"], "HOST");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn split_collections_keep_their_tag_and_file_apart() {
+    let dir = temp_dir("split_names");
+    run(&["to-postman", "--split", "tag", "-o", dir.to_str().unwrap(), fixture("split.json").to_str().unwrap()]);
+    // `pets store` and `pets/store` make the same file name
+    let pets = read_json(&dir.join("pets_store.postman_collection.json"));
+    assert_eq!(pets["info"]["name"], "pets store");
+    assert_eq!(pets["info"]["x-owner"], "pets-team");
+    let stores = read_json(&dir.join("pets_store_2.postman_collection.json"));
+    assert_eq!(stores["info"]["name"], "pets/store");
+    std::fs::remove_dir_all(dir).unwrap();
+}
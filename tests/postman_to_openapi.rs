@@ -0,0 +1,149 @@
+use serde_json::Value;
+
+mod common;
+use common::{fixture, read_json, run, temp_file};
+
+fn pts_with_stderr(collection: &str) -> (Value, String) {
+    let output = temp_file(&format!("pts_{}", collection));
+    let result = run(&["to-openapi", "-o", output.to_str().unwrap(), fixture(collection).to_str().unwrap()]);
+    let openapi = read_json(&output);
+    std::fs::remove_file(&output).unwrap();
+    (openapi, String::from_utf8(result.stderr).unwrap())
+}
+
+fn pts(collection: &str) -> Value {
+    pts_with_stderr(collection).0
+}
+
+fn parameter<'a>(operation: &'a Value, _in: &str, name: &str) -> &'a Value {
+    operation["parameters"].as_array().unwrap().iter()
+        .find(|p| p["in"] == _in && p["name"] == name)
+        .unwrap_or_else(|| panic!("missing {} parameter {}", _in, name))
+}
+
+#[test]
+fn infers_parameter_types_from_every_request() {
+    let openapi = pts("inference.postman.json");
+    let operation = &openapi["paths"]["/users/{id}"]["get"];
+    let page = parameter(operation, "query", "page");
+    assert_eq!(page["schema"]["type"], "number");
+    assert_eq!(page["required"], true);
+    assert_eq!(parameter(operation, "query", "active")["schema"]["type"], "boolean");
+    assert_eq!(parameter(operation, "path", "id")["schema"]["type"], "integer");
+}
+
+#[test]
+fn routes_transport_headers_out_of_parameters() {
+    let openapi = pts("transport_headers.postman.json");
+    let operation = &openapi["paths"]["/orders"]["post"];
+    let headers: Vec<&Value> = operation["parameters"].as_array().unwrap().iter()
+        .filter(|p| p["in"] == "header")
+        .map(|p| &p["name"])
+        .collect();
+    assert_eq!(headers, vec!["X-Tenant"]);
+    assert_eq!(operation["requestBody"]["content"]["application/xml"]["example"], "<order/>");
+    assert!(operation["responses"]["201"]["content"]["application/xml"].is_object());
+    assert_eq!(operation["security"], serde_json::json!([{ "X-API-Key": [], "bearer": [] }]));
+    assert_eq!(openapi["components"]["securitySchemes"]["X-API-Key"]["name"], "X-API-Key");
+}
+
+#[test]
+fn merges_requests_for_the_same_operation() {
+    let (openapi, stderr) = pts_with_stderr("merge.postman.json");
+    let operation = &openapi["paths"]["/users"]["post"];
+    assert_eq!(operation["tags"], serde_json::json!(["Users", "Admin"]));
+    assert_eq!(parameter(operation, "query", "notify")["required"], false);
+    parameter(operation, "header", "X-Tenant");
+    let body = &operation["requestBody"]["content"]["application/json"];
+    assert!(body["example"].is_null());
+    assert_eq!(body["examples"]["create user"]["value"], "{\"name\": \"ann\"}");
+    assert_eq!(body["examples"]["create admin"]["value"], "{\"name\": \"bob\", \"admin\": true}");
+    let created = &operation["responses"]["201"]["content"]["application/json"]["examples"];
+    assert_eq!(created.as_object().unwrap().len(), 2);
+    assert!(operation["responses"]["409"].is_object());
+    assert!(stderr.contains("merged 2 requests into POST /users: create user, create admin"));
+}
+
+#[test]
+fn keeps_saved_responses_with_the_same_status_as_examples() {
+    let openapi = pts("saved_responses.postman.json");
+    let content = &openapi["paths"]["/users/{id}"]["get"]["responses"]["200"]["content"]["application/json"];
+    assert_eq!(content["examples"]["active user"]["value"], "{\"active\": true}");
+    assert_eq!(content["examples"]["disabled user"]["value"], "{\"active\": false}");
+}
+
+#[test]
+fn converts_v1_collections() {
+    let openapi = pts("v1.postman.json");
+    let get = &openapi["paths"]["/users/{id}"]["get"];
+    assert_eq!(get["tags"], serde_json::json!(["Users"]));
+    assert_eq!(parameter(get, "path", "id")["schema"]["type"], "integer");
+    assert_eq!(parameter(get, "query", "verbose")["schema"]["type"], "boolean");
+    parameter(get, "header", "X-Tenant");
+    assert!(get["parameters"].as_array().unwrap().iter().all(|p| p["name"] != "X-Debug"));
+    assert!(get["responses"]["200"].is_object());
+    let post = &openapi["paths"]["/users"]["post"];
+    assert_eq!(post["requestBody"]["content"]["application/json"]["example"], "{\"name\": \"ann\"}");
+    assert_eq!(post["security"], serde_json::json!([{ "bearer": [] }]));
+    assert!(openapi["paths"]["/health"]["get"].is_object());
+    let upload = &openapi["paths"]["/users/{id}/avatar"]["put"]["requestBody"]["content"]["multipart/form-data"];
+    assert_eq!(upload["schema"]["properties"]["image"]["format"], "binary");
+    assert_eq!(upload["example"], serde_json::json!({ "caption": "me" }));
+    let login = &openapi["paths"]["/login"]["post"]["requestBody"]["content"]["application/x-www-form-urlencoded"];
+    assert_eq!(login["example"], serde_json::json!({ "user": "ann" }));
+}
+
+#[test]
+fn converts_v2_0_collections() {
+    let openapi = pts("v2.0.postman.json");
+    assert_eq!(openapi["paths"]["/me"]["get"]["security"], serde_json::json!([{ "bearer": [] }]));
+}
+
+#[test]
+fn reads_string_urls() {
+    let openapi = pts("string_url.postman.json");
+    let list = &openapi["paths"]["/orders"]["get"];
+    assert_eq!(parameter(list, "query", "status")["schema"]["example"], "open");
+    assert_eq!(parameter(list, "query", "limit")["schema"]["type"], "integer");
+    parameter(&openapi["paths"]["/orders/{id}"]["get"], "path", "id");
+}
+
+#[test]
+fn reads_description_objects_and_keeps_written_descriptions() {
+    let openapi = pts("descriptions.postman.json");
+    let operation = &openapi["paths"]["/orders/{id}"]["get"];
+    assert_eq!(operation["description"], "Reads an order");
+    assert_eq!(parameter(operation, "header", "X-Tenant")["description"], "Tenant of the caller");
+    assert_eq!(parameter(operation, "path", "id")["description"], "Order id");
+    let status = parameter(operation, "query", "status");
+    assert_eq!(status["description"], "Available values: depend on the account\n\nFilters by status");
+    assert!(status["schema"].get("enum").is_none());
+}
+
+#[test]
+fn converts_form_and_graphql_bodies() {
+    let openapi = pts("form_bodies.postman.json");
+    let multipart = &openapi["paths"]["/avatars"]["post"]["requestBody"]["content"]["multipart/form-data"];
+    assert_eq!(multipart["schema"]["properties"]["avatar"]["format"], "binary");
+    assert!(multipart["schema"]["properties"]["debug"].is_null());
+    assert_eq!(multipart["example"], serde_json::json!({ "name": "ann" }));
+    let form = &openapi["paths"]["/login"]["post"]["requestBody"]["content"]["application/x-www-form-urlencoded"];
+    assert_eq!(form["example"], serde_json::json!({ "username": "ann", "password": "secret" }));
+    let graphql = &openapi["paths"]["/graphql"]["post"]["requestBody"]["content"]["application/json"];
+    assert_eq!(graphql["example"], serde_json::json!({ "query": "query { me { id } }", "variables": { "first": 10 } }));
+}
+
+#[test]
+fn reports_requests_with_methods_openapi_has_no_operation_for() {
+    let (openapi, stderr) = pts_with_stderr("custom_method.postman.json");
+    let path_item = openapi["paths"]["/files/a.txt"].as_object().unwrap();
+    assert_eq!(path_item.keys().collect::<Vec<&String>>(), vec!["get"]);
+    assert!(stderr.contains("PURGE /files/a.txt dropped, an OpenAPI path item has no `purge` operation (/item/0/item/1/request/method)"), "{}", stderr);
+}
+
+#[test]
+fn keeps_auths_the_model_cannot_read() {
+    let (openapi, stderr) = pts_with_stderr("v1_bearer_token.postman.json");
+    assert!(openapi["paths"]["/me"]["get"].is_object());
+    assert!(stderr.contains("`bearer` auth of a shape the model cannot read (/item/1/request/auth)"), "{}", stderr);
+}
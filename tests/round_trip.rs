@@ -0,0 +1,130 @@
+mod common;
+use common::{fixture, read_json, run, temp_dir};
+
+#[test]
+fn round_trip_restores_fixture_specs() {
+    let out_dir = temp_dir("round_trip");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let config = fixture("round-trip-config.json");
+    let mut checked = 0;
+    for entry in std::fs::read_dir(fixture("round_trip")).unwrap() {
+        let spec = entry.unwrap().path();
+        let name = spec.file_stem().unwrap().to_str().unwrap().to_owned();
+        let collection = out_dir.join(format!("{}.postman.json", name));
+        let restored = out_dir.join(format!("{}.openapi.json", name));
+        run(&["to-postman", "-c", config.to_str().unwrap(), "-o", collection.to_str().unwrap(), spec.to_str().unwrap()]);
+        run(&["to-openapi", "-o", restored.to_str().unwrap(), collection.to_str().unwrap()]);
+        assert_eq!(read_json(&spec), read_json(&restored), "round trip changed {}", name);
+        checked += 1;
+    }
+    assert!(checked > 0);
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn round_trip_keeps_nested_folder_scripts_on_their_folder() {
+    let out_dir = temp_dir("nested");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let spec = out_dir.join("nested.openapi.json");
+    let collection = out_dir.join("nested.postman.json");
+    run(&["to-openapi", "-o", spec.to_str().unwrap(), fixture("nested_folders.postman.json").to_str().unwrap()]);
+    run(&["to-postman", "-o", collection.to_str().unwrap(), spec.to_str().unwrap()]);
+    let collection = read_json(&collection);
+    std::fs::remove_dir_all(&out_dir).unwrap();
+
+    let users = &collection["item"][0];
+    assert_eq!(users["name"], "users");
+    assert_eq!(users["event"][0]["script"]["exec"][0], "pm.variables.set('scope', 'users')");
+    let admin = users["item"].as_array().unwrap().iter().find(|item| item["name"] == "admin").unwrap();
+    assert_eq!(admin["event"].as_array().unwrap().len(), 1);
+    assert_eq!(admin["event"][0]["script"]["exec"][0], "pm.variables.set('role', 'admin')");
+    let requests = admin["item"].as_array().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|request| request.get("event").is_none()), "{:?}", requests);
+    assert!(users["item"].as_array().unwrap().iter().any(|item| item["request"]["method"] == "get" && item["name"] == "/users"));
+}
+
+#[test]
+fn round_trip_keeps_what_was_edited_in_postman() {
+    let out_dir = temp_dir("edited");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let collection = out_dir.join("petstore.postman.json");
+    let restored = out_dir.join("petstore.openapi.json");
+    let config = fixture("round-trip-config.json");
+    run(&["to-postman", "-c", config.to_str().unwrap(), "-o", collection.to_str().unwrap(), fixture("round_trip/petstore.json").to_str().unwrap()]);
+    let mut edited = read_json(&collection);
+    let requests = &mut edited["item"][0]["item"];
+    requests[0]["request"]["url"]["query"][0]["value"] = serde_json::json!("50");
+    requests[0]["request"]["header"].as_array_mut().unwrap().push(serde_json::json!({ "key": "X-Debug", "value": "1" }));
+    requests[0]["event"] = serde_json::json!([{ "listen": "prerequest", "script": { "type": "text/javascript", "exec": ["console.log(1)"] } }]);
+    requests[1]["request"]["body"]["raw"] = serde_json::json!("{\"id\":2,\"name\":\"Max\"}");
+    requests[2]["request"]["url"]["path"] = serde_json::json!(["api", "animals", ":petId"]);
+    std::fs::write(&collection, serde_json::to_string(&edited).unwrap()).unwrap();
+    run(&["to-openapi", "-o", restored.to_str().unwrap(), collection.to_str().unwrap()]);
+    let spec = read_json(&restored);
+    std::fs::remove_dir_all(&out_dir).unwrap();
+
+    let list = &spec["paths"]["/pets"]["get"];
+    assert_eq!(list["operationId"], "listPets");
+    let limit = list["parameters"].as_array().unwrap().iter().find(|p| p["name"] == "limit").unwrap();
+    assert_eq!(limit["schema"]["format"], "int32");
+    assert_eq!(limit["example"], 50);
+    assert!(list["parameters"].as_array().unwrap().iter().any(|p| p["name"] == "X-Debug" && p["in"] == "header"));
+    assert_eq!(list["x-postman-events"][0]["script"]["exec"][0], "console.log(1)");
+    assert_eq!(list["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"], "#/components/schemas/Pet");
+
+    let create = &spec["paths"]["/pets"]["post"];
+    assert_eq!(create["requestBody"]["content"]["application/json"]["example"], serde_json::json!({ "id": 2, "name": "Max" }));
+    assert_eq!(create["requestBody"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Pet");
+    assert_eq!(create["x-codegen-request-body-name"], "pet");
+
+    assert!(spec["paths"]["/pets/{petId}"].get("get").is_none());
+    let show = &spec["paths"]["/api/animals/{petId}"]["get"];
+    assert_eq!(show["operationId"], "showPetById");
+    assert_eq!(show["tags"], serde_json::json!(["pets"]));
+    let pet_id = show["parameters"].as_array().unwrap().iter().find(|p| p["name"] == "petId").unwrap();
+    assert_eq!(pet_id["schema"]["format"], "uuid");
+}
+
+#[test]
+fn request_variants_come_back_as_named_examples() {
+    let out_dir = temp_dir("variants");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let collection = out_dir.join("examples.postman.json");
+    let restored = out_dir.join("examples.openapi.json");
+    run(&["to-postman", "-c", fixture("generate-tests-config.json").to_str().unwrap(), "-o", collection.to_str().unwrap(), fixture("round_trip/examples.json").to_str().unwrap()]);
+    let written = read_json(&collection);
+    assert_eq!(written["item"][0]["item"][0]["event"][0]["listen"], "test");
+    let variant = &written["item"][0]["item"][1];
+    assert_eq!(variant["name"], "/users (guest)");
+    assert!(variant.get("event").is_none());
+    run(&["to-openapi", "-o", restored.to_str().unwrap(), collection.to_str().unwrap()]);
+    let spec = read_json(&restored);
+    std::fs::remove_dir_all(&out_dir).unwrap();
+
+    let examples = &spec["paths"]["/users"]["post"]["requestBody"]["content"]["application/json"]["examples"];
+    assert_eq!(examples["guest"]["value"], "{\"name\":\"guest\"}");
+    assert!(examples.get("/users (guest)").is_none());
+}
+
+#[test]
+fn reports_round_trip_data_that_cannot_be_read() {
+    let out_dir = temp_dir("unreadable");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let collection = out_dir.join("petstore.postman.json");
+    let restored = out_dir.join("petstore.openapi.json");
+    let config = fixture("round-trip-config.json");
+    run(&["to-postman", "-c", config.to_str().unwrap(), "-o", collection.to_str().unwrap(), fixture("round_trip/petstore.json").to_str().unwrap()]);
+    let mut edited = read_json(&collection);
+    edited["item"][0]["item"][2]["x-openapi-operation"] = serde_json::json!("edited by hand");
+    std::fs::write(&collection, serde_json::to_string(&edited).unwrap()).unwrap();
+    let result = run(&["to-openapi", "-o", restored.to_str().unwrap(), collection.to_str().unwrap()]);
+    let spec = read_json(&restored);
+    std::fs::remove_dir_all(&out_dir).unwrap();
+
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert!(stderr.contains("cannot read the exported operation, the request is converted as is"), "{}", stderr);
+    assert!(stderr.contains("(/item/0/item/2/x-openapi-operation)"), "{}", stderr);
+    assert!(spec["paths"]["/pets/{petId}"]["get"].get("operationId").is_none());
+    assert_eq!(spec["paths"]["/pets"]["get"]["operationId"], "listPets");
+}
@@ -0,0 +1,81 @@
+use serde_json::{json, Value};
+
+mod common;
+use common::{fixture, run, temp_file};
+
+/// The output of the converter and what it printed on stderr
+fn converted(args: &[&str]) -> (Value, String) {
+    let output = run(args);
+    (serde_json::from_slice(&output.stdout).unwrap(), String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+fn request<'a>(folder: &'a Value, name: &str) -> &'a Value {
+    folder["item"].as_array().unwrap().iter().find(|item| item["name"] == name).unwrap()
+}
+
+#[test]
+fn syncs_a_collection_keeping_what_was_edited() {
+    let (mut collection, _) = converted(&["to-postman", fixture("sync/v1.json").to_str().unwrap()]);
+    // what a user does to the generated collection
+    collection["variable"] = json!([{ "key": "HOST", "value": "https://pets.example.com" }]);
+    let list = &mut collection["item"][0]["item"][0];
+    list["name"] = json!("List pets");
+    list["event"] = json!([{ "listen": "test", "script": { "type": "text/javascript", "exec": ["pm.test('ok')"] } }]);
+    list["request"]["url"]["query"][0]["value"] = json!("50");
+    list["request"]["url"]["query"][0]["disabled"] = json!(false);
+    list["request"]["url"]["query"].as_array_mut().unwrap().push(json!({ "key": "trace", "value": "1" }));
+    list["request"]["header"].as_array_mut().unwrap().push(json!({ "key": "X-Debug", "value": "1", "type": "text" }));
+    let existing = temp_file("sync");
+    std::fs::write(&existing, serde_json::to_string(&collection).unwrap()).unwrap();
+
+    let (synced, summary) = converted(&["sync", existing.to_str().unwrap(), fixture("sync/v2.json").to_str().unwrap()]);
+    std::fs::remove_file(existing).unwrap();
+    assert!(summary.starts_with("1 updated, 2 added, 1 removed"), "{}", summary);
+    assert_eq!(synced["variable"][0]["value"], "https://pets.example.com");
+
+    let folders: Vec<&str> = synced["item"].as_array().unwrap().iter().map(|folder| folder["name"].as_str().unwrap()).collect();
+    assert_eq!(folders, vec!["pets", "owners", "_removed"]);
+    let list = request(&synced["item"][0], "List pets");
+    assert_eq!(list["event"][0]["script"]["exec"][0], "pm.test('ok')");
+    let query: Vec<(&str, &str)> = list["request"]["url"]["query"].as_array().unwrap().iter()
+        .map(|query| (query["key"].as_str().unwrap(), query["value"].as_str().unwrap()))
+        .collect();
+    assert_eq!(query, vec![("limit", "50"), ("sort", "name"), ("trace", "1")]);
+    assert!(list["request"]["header"].as_array().unwrap().iter().any(|header| header["key"] == "X-Debug" && header["value"] == "1"));
+    assert_eq!(list["request"]["url"]["query"][0]["disabled"], false);
+
+    let create = request(&synced["item"][0], "/pets");
+    assert_eq!(create["request"]["method"], "post");
+    assert_eq!(request(&synced["item"][1], "/owners")["request"]["method"], "get");
+    assert_eq!(request(&synced["item"][2], "/pets/{petId}")["request"]["method"], "delete");
+}
+
+#[test]
+fn syncs_a_renamed_path_by_operation_id_without_round_trip() {
+    let (mut collection, _) = converted(&["to-postman", fixture("sync/renamed_v1.json").to_str().unwrap()]);
+    collection["item"][0]["item"][0]["name"] = json!("List pets");
+    let existing = temp_file("sync_renamed");
+    std::fs::write(&existing, serde_json::to_string(&collection).unwrap()).unwrap();
+
+    let (synced, summary) = converted(&["sync", existing.to_str().unwrap(), fixture("sync/renamed_v2.json").to_str().unwrap()]);
+    std::fs::remove_file(existing).unwrap();
+    assert!(summary.starts_with("1 updated, 0 added, 0 removed"), "{}", summary);
+    let list = request(&synced["item"][0], "List pets");
+    assert_eq!(list["request"]["url"]["path"], json!(["animals"]));
+    assert_eq!(list["x-openapi-operation-id"], "listPets");
+}
+
+#[test]
+fn syncs_requests_in_nested_folders_in_place() {
+    let spec = temp_file("sync_nested_spec");
+    run(&["to-openapi", "-o", spec.to_str().unwrap(), fixture("nested_folders.postman.json").to_str().unwrap()]);
+    let (synced, summary) = converted(&["sync", fixture("nested_folders.postman.json").to_str().unwrap(), spec.to_str().unwrap()]);
+    std::fs::remove_file(spec).unwrap();
+    assert!(summary.starts_with("3 updated, 0 added, 0 removed"), "{}", summary);
+    let users = &synced["item"][0];
+    let names: Vec<&str> = users["item"].as_array().unwrap().iter().map(|item| item["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["admin", "list users"]);
+    let admin = request(users, "admin");
+    assert_eq!(request(admin, "list admins")["request"]["method"], "get");
+    assert_eq!(request(admin, "delete admin")["request"]["method"], "delete");
+}